@@ -1,14 +1,24 @@
 use crate::{
-    cache::DiffCache,
+    cache::{DiffCache, DiskCache, ReviewThreadCache},
     diff::DiffParser,
-    github::{Commit, FileChange, GitHubClient, PullRequest},
+    events::Action,
+    forge::Forge,
+    github::{
+        CheckAnnotation, Commit, CommitChecks, DiffLine, DiffSide, DraftReviewComment, FileChange,
+        GitHubClient, LineType, NewReviewComment, ParsedPrUrl, PullRequest, ReviewEvent,
+        ReviewThreads,
+    },
     settings::Settings,
     theme::Theme,
-    ui::{DiffView, Navigation, Sidebar},
+    ui::{
+        scroll::ScrollState, ChecksView, CommentInput, CommentTarget, ConversationView, DiffView,
+        Navigation, PrOverview, ReviewDialog, Sidebar,
+    },
 };
 use anyhow::Result;
 use futures::future::join_all;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
+use tokio::task::JoinHandle;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FocusedPane {
@@ -16,10 +26,44 @@ pub enum FocusedPane {
     DiffView,
 }
 
+/// The screen shown above the navigation bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Screen {
+    Diff,
+    Overview,
+    Conversation,
+    Checks,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InputMode {
     Normal,
     Search,
+    Comment,
+    Review,
+}
+
+/// A transient message shown in the navigation bar
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+}
+
+impl StatusMessage {
+    pub fn info(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_error: false,
+        }
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_error: true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -65,6 +109,10 @@ impl LoadingStatus {
                     name: "Processing diffs".to_string(),
                     status: LoadingStepStatus::Pending,
                 },
+                LoadingStep {
+                    name: "Loading review comments".to_string(),
+                    status: LoadingStepStatus::Pending,
+                },
             ],
             current_message: "Initializing...".to_string(),
         }
@@ -81,6 +129,14 @@ impl LoadingStatus {
     }
 }
 
+/// Review created on GitHub by a submission that failed, to be reused by the next one
+#[derive(Debug, Clone, Copy)]
+struct PendingReview {
+    id: u64,
+    /// Number of drafts, from the front of the queue, attached to the review
+    comments: usize,
+}
+
 pub enum AppState {
     Loading(LoadingStatus),
     Ready,
@@ -96,7 +152,10 @@ pub struct App {
     pub navigation: Option<Navigation>,
     pub files: Vec<FileChange>,
     pub commits: Vec<Commit>,
-    pub client: GitHubClient,
+    /// Source of the pull request's commits, files and contents
+    pub forge: Arc<dyn Forge>,
+    /// Comments, reviews, the conversation and CI checks are only available on GitHub
+    pub github: Option<GitHubClient>,
     pub owner: String,
     pub repo: String,
     pub pr_number: u64,
@@ -104,6 +163,27 @@ pub struct App {
     pub theme: Theme,
     pub focused_pane: FocusedPane,
     pub input_mode: InputMode,
+    pub screen: Screen,
+    pub overview: PrOverview,
+    pub conversation: ConversationView,
+    pub checks_view: ChecksView,
+    pub comment_input: CommentInput,
+    pub review_dialog: ReviewDialog,
+    /// Line comments queued for the next review submission
+    pub draft_comments: Vec<NewReviewComment>,
+    pub status_message: Option<StatusMessage>,
+    /// Warning shown above the review until the app is closed
+    pub banner: Option<String>,
+    /// Existing review comment threads, indexed by file and line
+    pub review_threads: ReviewThreadCache,
+    /// CI check runs and statuses, indexed by commit SHA
+    pub commit_checks: HashMap<String, CommitChecks>,
+    /// CI checks being fetched in the background
+    checks_task: Option<JoinHandle<HashMap<String, CommitChecks>>>,
+    /// Check run annotations, indexed by commit SHA and fetched when a commit is first shown
+    commit_annotations: HashMap<String, Vec<CheckAnnotation>>,
+    /// Pending review created on GitHub but not yet submitted
+    pending_review: Option<PendingReview>,
     /// Cache of commit files indexed by commit SHA
     commit_files_cache: HashMap<String, Vec<FileChange>>,
     /// All files changed in the PR (fetched once)
@@ -113,12 +193,14 @@ pub struct App {
 }
 
 impl App {
-    pub async fn new(pr_url: &str, token: Option<String>) -> Result<Self> {
-        let client = GitHubClient::new(token).await?;
-        let parsed = GitHubClient::parse_pr_url(pr_url)?;
-
-        // Load settings and theme
-        let settings = Settings::load().unwrap_or_default();
+    pub fn new(
+        parsed: ParsedPrUrl,
+        forge: Arc<dyn Forge>,
+        github: Option<GitHubClient>,
+        settings: Settings,
+        disk_cache: Option<DiskCache>,
+    ) -> Result<Self> {
+        // Load the theme
         let theme = settings.get_theme().unwrap_or_else(|_| {
             Theme::load("catppuccin-mocha").expect("Failed to load default theme")
         });
@@ -135,7 +217,8 @@ impl App {
             navigation: None,
             files: Vec::new(),
             commits: Vec::new(),
-            client,
+            forge,
+            github,
             owner: parsed.owner,
             repo: parsed.repo,
             pr_number: parsed.number,
@@ -143,9 +226,23 @@ impl App {
             theme,
             focused_pane: FocusedPane::Sidebar,
             input_mode: InputMode::Normal,
+            screen: Screen::Diff,
+            overview: PrOverview::new(),
+            conversation: ConversationView::new(),
+            checks_view: ChecksView::new(),
+            comment_input: CommentInput::new(),
+            review_dialog: ReviewDialog::new(),
+            draft_comments: Vec::new(),
+            status_message: None,
+            banner: None,
+            review_threads: ReviewThreadCache::default(),
+            commit_checks: HashMap::new(),
+            checks_task: None,
+            commit_annotations: HashMap::new(),
+            pending_review: None,
             commit_files_cache: HashMap::new(),
             pr_files: None,
-            diff_cache: DiffCache::new(50),
+            diff_cache: DiffCache::persistent(50, disk_cache),
         })
     }
 
@@ -158,7 +255,7 @@ impl App {
         self.state = AppState::Loading(loading_status.clone());
 
         let pr = self
-            .client
+            .forge
             .get_pull_request(&self.owner, &self.repo, self.pr_number)
             .await?;
         self.pr = Some(pr.clone());
@@ -171,12 +268,14 @@ impl App {
         self.state = AppState::Loading(loading_status.clone());
 
         let commits = self
-            .client
+            .forge
             .get_pr_commits(&self.owner, &self.repo, self.pr_number)
             .await?;
         let commit_count = commits.len();
         self.commits = commits.clone();
-        self.navigation = Some(Navigation::new(commits));
+        let mut navigation = Navigation::new(commits);
+        navigation.pending_comments = self.draft_comments.len();
+        self.navigation = Some(navigation);
 
         loading_status.update_step(2, LoadingStepStatus::Completed);
         loading_status.steps[2].name = format!("Loading commits ({commit_count} found)");
@@ -187,9 +286,12 @@ impl App {
         self.state = AppState::Loading(loading_status.clone());
 
         let pr_files = self
-            .client
+            .forge
             .get_pr_files(&self.owner, &self.repo, self.pr_number)
             .await?;
+        if let Some(ref mut pr) = self.pr {
+            pr.count_missing_totals(commit_count, &pr_files);
+        }
         self.pr_files = Some(pr_files);
 
         loading_status.update_step(3, LoadingStepStatus::Completed);
@@ -212,10 +314,96 @@ impl App {
             loading_status.update_step(4, LoadingStepStatus::Completed);
         }
 
+        // Review comments are shown inline, failing to load them isn't fatal
+        loading_status.update_step(5, LoadingStepStatus::InProgress);
+        loading_status.set_current_message("Fetching review comments...".to_string());
+        self.state = AppState::Loading(loading_status.clone());
+        self.reload_review_comments().await;
+        loading_status.update_step(5, LoadingStepStatus::Completed);
+        loading_status.steps[5].name = format!(
+            "Loading review comments ({} threads found)",
+            self.review_threads.len()
+        );
+
+        // CI results only decorate the commits, so they're loaded once the review is shown
+        self.reload_checks();
+
         self.state = AppState::Ready;
         Ok(())
     }
 
+    /// Show `screen`, or go back to the diff if it's already shown
+    pub async fn toggle_screen(&mut self, screen: Screen) {
+        if self.screen == screen {
+            self.screen = Screen::Diff;
+            return;
+        }
+        if matches!(screen, Screen::Conversation | Screen::Checks)
+            && self.require_github().is_none()
+        {
+            return;
+        }
+
+        self.screen = screen;
+        // The timeline isn't needed for the diff, so it's only fetched once it's shown
+        if screen == Screen::Conversation && !self.conversation.loaded {
+            self.reload_timeline().await;
+        }
+    }
+
+    /// Scroll state of the current screen, if it scrolls as a single page
+    pub fn screen_scroll(&mut self) -> Option<&mut ScrollState> {
+        match self.screen {
+            Screen::Diff => None,
+            Screen::Overview => Some(&mut self.overview.scroll),
+            Screen::Conversation => Some(&mut self.conversation.scroll),
+            Screen::Checks => Some(&mut self.checks_view.scroll),
+        }
+    }
+
+    /// Handle an action while a screen other than the diff is shown
+    pub async fn handle_screen_action(&mut self, action: Action) -> Result<()> {
+        if let Some(scroll) = self.screen_scroll() {
+            match action {
+                Action::NavigateUp | Action::ScrollUp => scroll.scroll_up(1),
+                Action::NavigateDown | Action::ScrollDown => scroll.scroll_down(1),
+                Action::PageUp => scroll.page_up(),
+                Action::PageDown => scroll.page_down(),
+                Action::Home => scroll.scroll_to_top(),
+                Action::End => scroll.scroll_to_bottom(),
+                _ => {}
+            }
+        }
+
+        match action {
+            // Leaving a screen takes precedence over quitting
+            Action::Quit => self.screen = Screen::Diff,
+            Action::ToggleOverview => self.toggle_screen(Screen::Overview).await,
+            Action::ToggleConversation => self.toggle_screen(Screen::Conversation).await,
+            Action::ToggleChecks => self.toggle_screen(Screen::Checks).await,
+            // Step through commits to find the one that broke CI
+            Action::NextCommit if self.screen == Screen::Checks => {
+                self.handle_next_commit().await?;
+                self.checks_view.scroll.scroll_to_top();
+            }
+            Action::PrevCommit if self.screen == Screen::Checks => {
+                self.handle_prev_commit().await?;
+                self.checks_view.scroll.scroll_to_top();
+            }
+            Action::AddComment if self.screen == Screen::Conversation => {
+                if self.require_github().is_none() {
+                    return Ok(());
+                }
+                self.comment_input.clear();
+                self.input_mode = InputMode::Comment;
+            }
+            Action::Refresh => self.handle_refresh().await?,
+            Action::CycleTheme => self.cycle_theme()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
     pub fn toggle_focus(&mut self) {
         self.focused_pane = match self.focused_pane {
             FocusedPane::Sidebar => FocusedPane::DiffView,
@@ -236,15 +424,11 @@ impl App {
                 if let Some(index) = selected_index {
                     // Always load the file's diff content when navigating
                     self.load_file_diff(index).await?;
-                    if let Some(ref sidebar) = self.sidebar {
-                        if let Some(file) = sidebar.get_selected_file() {
-                            self.diff_view.set_file(Some(file.clone()));
-                        }
-                    }
+                    self.show_selected_file();
                 }
             }
             FocusedPane::DiffView => {
-                self.diff_view.scroll_up(1);
+                self.diff_view.cursor_up();
             }
         }
         Ok(())
@@ -263,15 +447,11 @@ impl App {
                 if let Some(index) = selected_index {
                     // Always load the file's diff content when navigating
                     self.load_file_diff(index).await?;
-                    if let Some(ref sidebar) = self.sidebar {
-                        if let Some(file) = sidebar.get_selected_file() {
-                            self.diff_view.set_file(Some(file.clone()));
-                        }
-                    }
+                    self.show_selected_file();
                 }
             }
             FocusedPane::DiffView => {
-                self.diff_view.scroll_down(1);
+                self.diff_view.cursor_down();
             }
         }
         Ok(())
@@ -320,6 +500,7 @@ impl App {
             }
 
             self.load_commit_files(current_index).await?;
+            self.load_annotations().await;
 
             // Only reset state if we showed loading
             if !is_cached {
@@ -340,16 +521,20 @@ impl App {
         let files = if let Some(cached_files) = self.commit_files_cache.get(&commit.sha) {
             // Use cached files (instant!)
             cached_files.clone()
-        } else if commit_index == self.commits.len() - 1 && self.pr_files.is_some() {
+        } else if let Some(pr_files) = self
+            .pr_files
+            .as_ref()
+            .filter(|_| commit_index == self.commits.len() - 1)
+        {
             // For the last commit (all changes in PR), use PR files directly
-            let pr_files = self.pr_files.as_ref().unwrap().clone();
+            let pr_files = pr_files.clone();
             self.commit_files_cache
                 .insert(commit.sha.clone(), pr_files.clone());
             pr_files
         } else {
             // Need to fetch from API (only as last resort)
             let fetched_files = self
-                .client
+                .forge
                 .get_commit_files(&self.owner, &self.repo, &commit.sha)
                 .await?;
 
@@ -372,15 +557,172 @@ impl App {
         // Select the first file in the diff view (for UI display)
         // The file should already have a patch from the API, so it will show something
         // The full diff_content will be loaded lazily when user navigates
-        if let Some(ref sidebar) = self.sidebar {
-            if let Some(file) = sidebar.get_selected_file() {
-                self.diff_view.set_file(Some(file.clone()));
-            } else {
-                self.diff_view.set_file(None);
+        self.show_selected_file();
+
+        Ok(())
+    }
+
+    /// Show the file selected in the sidebar, along with its review threads
+    pub fn show_selected_file(&mut self) {
+        let file = self
+            .sidebar
+            .as_ref()
+            .and_then(|sidebar| sidebar.get_selected_file())
+            .cloned();
+        let threads = file
+            .as_ref()
+            .map(|f| self.review_threads.for_file(&f.filename))
+            .unwrap_or_default();
+
+        self.diff_view.set_file(file);
+        self.diff_view.set_file_threads(threads);
+        self.diff_view
+            .set_file_annotations(self.current_file_annotations());
+    }
+
+    /// Annotations of the selected commit on the file shown in the diff view
+    fn current_file_annotations(&self) -> Vec<CheckAnnotation> {
+        let (Some(sha), Some(file)) = (self.current_commit_sha(), &self.diff_view.current_file)
+        else {
+            return Vec::new();
+        };
+        self.commit_annotations
+            .get(&sha)
+            .map(|annotations| {
+                annotations
+                    .iter()
+                    .filter(|a| a.path == file.filename)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Fetch the check run annotations of the selected commit, unless already loaded
+    pub async fn load_annotations(&mut self) {
+        let Some(github) = self.github.clone() else {
+            return;
+        };
+        let Some(sha) = self.current_commit_sha() else {
+            return;
+        };
+        if self.commit_annotations.contains_key(&sha) {
+            return;
+        }
+        let Some(checks) = self.commit_checks.get(&sha) else {
+            return;
+        };
+
+        match github
+            .get_check_annotations(&self.owner, &self.repo, checks)
+            .await
+        {
+            Ok(annotations) => {
+                self.commit_annotations.insert(sha, annotations);
+                self.diff_view
+                    .set_file_annotations(self.current_file_annotations());
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(format!(
+                    "Failed to load check annotations: {e:#}"
+                )));
             }
         }
+    }
 
-        Ok(())
+    /// Replace the review threads shown inline in the diff
+    pub fn set_review_threads(&mut self, threads: ReviewThreads) {
+        if let Some(warning) = threads.warning {
+            self.status_message = Some(StatusMessage::error(warning));
+        }
+        self.review_threads = ReviewThreadCache::new(threads.threads);
+
+        let threads = self
+            .diff_view
+            .current_file
+            .as_ref()
+            .map(|f| self.review_threads.for_file(&f.filename))
+            .unwrap_or_default();
+        self.diff_view.set_file_threads(threads);
+    }
+
+    /// Fetch the PR's review threads again, e.g. after posting a comment
+    pub async fn reload_review_comments(&mut self) {
+        let Some(github) = self.github.clone() else {
+            return;
+        };
+        match github
+            .get_review_threads(&self.owner, &self.repo, self.pr_number)
+            .await
+        {
+            Ok(threads) => self.set_review_threads(threads),
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(format!(
+                    "Failed to load review comments: {e:#}"
+                )));
+            }
+        }
+    }
+
+    pub fn set_commit_checks(&mut self, checks: HashMap<String, CommitChecks>) {
+        if let Some(ref mut nav) = self.navigation {
+            nav.set_checks(&checks);
+        }
+        self.commit_checks = checks;
+        self.commit_annotations.clear();
+    }
+
+    /// Start fetching CI checks for every commit in the PR in the background
+    pub fn reload_checks(&mut self) {
+        let Some(github) = self.github.clone() else {
+            return;
+        };
+        let (owner, repo) = (self.owner.clone(), self.repo.clone());
+        let shas: Vec<String> = self.commits.iter().map(|c| c.sha.clone()).collect();
+
+        if let Some(task) = self.checks_task.take() {
+            task.abort();
+        }
+        self.checks_task = Some(tokio::spawn(async move {
+            github.get_checks_for_commits(&owner, &repo, &shas).await
+        }));
+    }
+
+    /// Show the CI checks once they've been fetched in the background
+    pub async fn poll_checks(&mut self) {
+        let Some(task) = self.checks_task.take_if(|t| t.is_finished()) else {
+            return;
+        };
+        let Ok(checks) = task.await else {
+            return;
+        };
+
+        let missing = self.commits.len().saturating_sub(checks.len());
+        self.set_commit_checks(checks);
+        if missing > 0 {
+            self.status_message = Some(StatusMessage::error(format!(
+                "Failed to load CI checks of {missing} commits"
+            )));
+        }
+        self.load_annotations().await;
+    }
+
+    /// Fetch the PR's conversation timeline again
+    pub async fn reload_timeline(&mut self) {
+        let Some(github) = self.github.clone() else {
+            return;
+        };
+        match github
+            .get_timeline(&self.owner, &self.repo, self.pr_number)
+            .await
+        {
+            Ok(items) => self.conversation.set_items(items),
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(format!(
+                    "Failed to load the conversation: {e:#}"
+                )));
+            }
+        }
     }
 
     /// Load diff content for a specific file on demand
@@ -418,16 +760,22 @@ impl App {
                     // Use cached diff
                     self.files[file_index].diff_content = Some(cached_diff);
                 } else {
-                    // Calculate diff and cache it
-                    DiffParser::enrich_single_file(
+                    // Calculate diff and cache it. The file's patch is shown if it fails.
+                    if let Err(e) = DiffParser::enrich_single_file(
                         &mut self.files[file_index],
-                        &self.client,
+                        self.forge.as_ref(),
                         &self.owner,
                         &self.repo,
                         &base_sha,
                         &commit.sha,
                     )
-                    .await?;
+                    .await
+                    {
+                        self.status_message = Some(StatusMessage::error(format!(
+                            "Failed to load {}: {e:#}",
+                            self.files[file_index].filename
+                        )));
+                    }
 
                     // Cache the diff for future use
                     if let Some(ref diff) = self.files[file_index].diff_content {
@@ -477,6 +825,462 @@ impl App {
         self.diff_view.prev_hunk();
     }
 
+    /// Open the comment editor for the diff line under the cursor
+    pub fn start_comment(&mut self) {
+        if self.require_github().is_none() {
+            return;
+        }
+        let Some(target) = self.comment_target() else {
+            self.status_message = Some(StatusMessage::error(
+                "Move the cursor to a diff line to comment on it",
+            ));
+            return;
+        };
+
+        self.comment_input.start(target);
+        self.input_mode = InputMode::Comment;
+    }
+
+    /// Open the comment editor with a suggestion block replacing the selected lines
+    pub fn start_suggestion(&mut self) {
+        if self.require_github().is_none() {
+            return;
+        }
+        let Some((target, original)) = self.suggestion_target() else {
+            self.status_message = Some(StatusMessage::error(
+                "Select lines of the new file to suggest a change",
+            ));
+            return;
+        };
+
+        self.comment_input.start_suggestion(target, original);
+        self.input_mode = InputMode::Comment;
+    }
+
+    pub fn cancel_comment(&mut self) {
+        self.comment_input.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Open the comment editor to reply to the review thread under the cursor
+    pub fn start_reply(&mut self) {
+        if self.require_github().is_none() {
+            return;
+        }
+        let Some(thread) = self.diff_view.selected_thread() else {
+            self.status_message = Some(StatusMessage::error(
+                "Move the cursor to a line with a review thread to reply",
+            ));
+            return;
+        };
+        let Some(thread_id) = thread.node_id.clone() else {
+            self.status_message = Some(StatusMessage::error(
+                "Replying to threads requires a GitHub token",
+            ));
+            return;
+        };
+
+        let root = thread.root();
+        let target = CommentTarget {
+            path: root.path.clone(),
+            line: root.line.or(root.original_line).unwrap_or(0),
+            side: thread.side(),
+            start_line: None,
+            start_side: None,
+            commit_id: root.commit_id.clone(),
+            reply_to: Some(thread_id),
+        };
+
+        self.comment_input.start(target);
+        self.input_mode = InputMode::Comment;
+    }
+
+    /// Resolve the review thread under the cursor, or unresolve it if already resolved
+    pub async fn toggle_thread_resolved(&mut self) {
+        let Some(github) = self.require_github() else {
+            return;
+        };
+        let Some(thread) = self.diff_view.selected_thread() else {
+            self.status_message = Some(StatusMessage::error(
+                "Move the cursor to a line with a review thread to resolve it",
+            ));
+            return;
+        };
+        let Some(thread_id) = thread.node_id.clone() else {
+            self.status_message = Some(StatusMessage::error(
+                "Resolving threads requires a GitHub token",
+            ));
+            return;
+        };
+        let resolve = !thread.is_resolved;
+
+        match github.set_review_thread_resolved(&thread_id, resolve).await {
+            Ok(()) => {
+                self.status_message = Some(StatusMessage::info(if resolve {
+                    "Thread resolved"
+                } else {
+                    "Thread unresolved"
+                }));
+                self.reload_review_comments().await;
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(format!("{e:#}")));
+            }
+        }
+    }
+
+    /// Post the comment being edited as a pull request review comment
+    pub async fn submit_comment(&mut self) {
+        let Some(github) = self.require_github() else {
+            return;
+        };
+        if self.comment_input.target.is_none() {
+            self.submit_conversation_comment().await;
+            return;
+        }
+        if let Some(thread_id) = self
+            .comment_input
+            .target
+            .as_ref()
+            .and_then(|t| t.reply_to.clone())
+        {
+            self.submit_reply(&thread_id).await;
+            return;
+        }
+
+        let Some(comment) = self.comment_from_input() else {
+            return;
+        };
+
+        match github
+            .create_review_comment(&self.owner, &self.repo, self.pr_number, &comment)
+            .await
+        {
+            Ok(()) => {
+                self.status_message = Some(StatusMessage::info(format!(
+                    "Comment posted on {}:{}",
+                    comment.path, comment.line
+                )));
+                self.cancel_comment();
+                self.diff_view.clear_selection();
+                self.reload_review_comments().await;
+            }
+            Err(e) => {
+                // Keep the editor open so the comment isn't lost
+                self.status_message = Some(StatusMessage::error(format!("{e:#}")));
+            }
+        }
+    }
+
+    async fn submit_conversation_comment(&mut self) {
+        let Some(github) = self.require_github() else {
+            return;
+        };
+        if self.comment_input.is_empty() {
+            return;
+        }
+
+        match github
+            .create_issue_comment(
+                &self.owner,
+                &self.repo,
+                self.pr_number,
+                &self.comment_input.text,
+            )
+            .await
+        {
+            Ok(()) => {
+                self.status_message = Some(StatusMessage::info("Comment posted"));
+                self.cancel_comment();
+                self.reload_timeline().await;
+                self.conversation.scroll.scroll_to_bottom();
+            }
+            Err(e) => {
+                // Keep the editor open so the comment isn't lost
+                self.status_message = Some(StatusMessage::error(format!("{e:#}")));
+            }
+        }
+    }
+
+    async fn submit_reply(&mut self, thread_id: &str) {
+        let Some(github) = self.require_github() else {
+            return;
+        };
+        if self.comment_input.is_empty() {
+            return;
+        }
+
+        match github
+            .reply_to_review_thread(thread_id, &self.comment_input.text)
+            .await
+        {
+            Ok(()) => {
+                self.status_message = Some(StatusMessage::info("Reply posted"));
+                self.cancel_comment();
+                self.reload_review_comments().await;
+            }
+            Err(e) => {
+                // Keep the editor open so the reply isn't lost
+                self.status_message = Some(StatusMessage::error(format!("{e:#}")));
+            }
+        }
+    }
+
+    /// Queue the comment being edited as a draft for the next review
+    pub fn add_draft_comment(&mut self) {
+        match self.comment_input.target {
+            Some(ref target) if target.reply_to.is_some() => {
+                self.status_message = Some(StatusMessage::error(
+                    "Replies are posted right away, press Ctrl+s to send",
+                ));
+                return;
+            }
+            None => {
+                self.status_message = Some(StatusMessage::error(
+                    "Only line comments can be added to a review, press Ctrl+s to post",
+                ));
+                return;
+            }
+            Some(_) => {}
+        }
+
+        let Some(comment) = self.comment_from_input() else {
+            return;
+        };
+
+        // A review is created against a single commit, so drafts can't mix commits
+        if let Some(first) = self.draft_comments.first() {
+            if first.commit_id != comment.commit_id {
+                let short_sha: String = first.commit_id.chars().take(7).collect();
+                self.status_message = Some(StatusMessage::error(format!(
+                    "Pending review is on commit {short_sha}; submit it or post a single comment"
+                )));
+                return;
+            }
+        }
+
+        self.draft_comments.push(comment);
+        self.sync_pending_comments();
+        self.status_message = Some(StatusMessage::info(format!(
+            "Added to review ({} pending)",
+            self.draft_comments.len()
+        )));
+        self.cancel_comment();
+        self.diff_view.clear_selection();
+    }
+
+    /// Open the review submission dialog
+    pub fn start_review(&mut self) {
+        if self.require_github().is_none() {
+            return;
+        }
+        self.review_dialog.reset();
+        self.input_mode = InputMode::Review;
+    }
+
+    pub fn cancel_review(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Create a pending review with all draft comments and submit it
+    pub async fn submit_review(&mut self) {
+        let Some(github) = self.require_github() else {
+            return;
+        };
+        let event = self.review_dialog.event;
+        let body = self.review_dialog.body.text.trim().to_string();
+
+        // GitHub only accepts an empty summary when approving or when comments are attached
+        let needs_body = match event {
+            ReviewEvent::Approve => false,
+            ReviewEvent::RequestChanges => true,
+            ReviewEvent::Comment => self.draft_comments.is_empty(),
+        };
+        if needs_body && body.is_empty() {
+            self.status_message = Some(StatusMessage::error(format!(
+                "A summary is required to {}",
+                event.label().to_lowercase()
+            )));
+            return;
+        }
+
+        let commit_id = match self.draft_comments.first() {
+            Some(draft) => Some(draft.commit_id.clone()),
+            None => self.current_commit_sha(),
+        };
+        let Some(commit_id) = commit_id else {
+            return;
+        };
+
+        // Reuse a pending review left over from a failed submission, unless drafts were added
+        // since. Its comments can't be added to, so it's replaced by one with all of them.
+        let pending = match self.pending_review.take() {
+            Some(pending) if pending.comments == self.draft_comments.len() => pending,
+            stale => {
+                if let Some(stale) = stale {
+                    if let Err(e) = github
+                        .delete_pending_review(&self.owner, &self.repo, self.pr_number, stale.id)
+                        .await
+                    {
+                        self.pending_review = Some(stale);
+                        self.status_message = Some(StatusMessage::error(format!("{e:#}")));
+                        return;
+                    }
+                }
+
+                let comments: Vec<DraftReviewComment> =
+                    self.draft_comments.iter().map(Into::into).collect();
+                match github
+                    .create_pending_review(
+                        &self.owner,
+                        &self.repo,
+                        self.pr_number,
+                        &commit_id,
+                        &comments,
+                    )
+                    .await
+                {
+                    Ok(id) => PendingReview {
+                        id,
+                        comments: comments.len(),
+                    },
+                    Err(e) => {
+                        self.status_message = Some(StatusMessage::error(format!("{e:#}")));
+                        return;
+                    }
+                }
+            }
+        };
+        let (review_id, sent) = (pending.id, pending.comments);
+        self.pending_review = Some(pending);
+
+        match github
+            .submit_review(
+                &self.owner,
+                &self.repo,
+                self.pr_number,
+                review_id,
+                event,
+                &body,
+            )
+            .await
+        {
+            Ok(()) => {
+                self.pending_review = None;
+                self.draft_comments.drain(..sent);
+                self.sync_pending_comments();
+                self.review_dialog.reset();
+                self.input_mode = InputMode::Normal;
+                self.status_message = Some(StatusMessage::info(format!(
+                    "Review submitted ({})",
+                    event.label()
+                )));
+                self.reload_review_comments().await;
+            }
+            Err(e) => {
+                self.status_message = Some(StatusMessage::error(format!("{e:#}")));
+            }
+        }
+    }
+
+    /// Build a review comment from the text and target in the comment editor
+    fn comment_from_input(&self) -> Option<NewReviewComment> {
+        if self.comment_input.is_empty() {
+            return None;
+        }
+        let target = self.comment_input.target.clone()?;
+
+        Some(NewReviewComment {
+            body: self.comment_input.text.clone(),
+            commit_id: target.commit_id,
+            path: target.path,
+            line: target.line,
+            side: target.side,
+            start_line: target.start_line,
+            start_side: target.start_side,
+        })
+    }
+
+    fn sync_pending_comments(&mut self) {
+        if let Some(ref mut nav) = self.navigation {
+            nav.pending_comments = self.draft_comments.len();
+        }
+    }
+
+    /// SHA of the commit selected in the navigation bar, or the PR head
+    fn current_commit_sha(&self) -> Option<String> {
+        match self
+            .navigation
+            .as_ref()
+            .and_then(|n| n.get_current_commit())
+        {
+            Some(commit) => Some(commit.sha.clone()),
+            None => self.pr.as_ref().map(|pr| pr.head.sha.clone()),
+        }
+    }
+
+    /// Build the comment anchor for the diff line under the cursor
+    fn comment_target(&self) -> Option<CommentTarget> {
+        let file = self.diff_view.current_file.as_ref()?;
+        let anchors: Vec<(usize, DiffSide)> = self
+            .diff_view
+            .selected_lines()
+            .iter()
+            .filter_map(line_anchor)
+            .collect();
+
+        let &(line, side) = anchors.last()?;
+        let start = anchors
+            .first()
+            .copied()
+            .filter(|&first| first != (line, side));
+        let commit_id = self.current_commit_sha()?;
+
+        Some(CommentTarget {
+            path: file.filename.clone(),
+            line,
+            side,
+            start_line: start.map(|(line, _)| line),
+            start_side: start.map(|(_, side)| side),
+            commit_id,
+            reply_to: None,
+        })
+    }
+
+    /// Build the comment anchor and original content for a suggestion on the selected lines
+    fn suggestion_target(&self) -> Option<(CommentTarget, String)> {
+        let file = self.diff_view.current_file.as_ref()?;
+
+        // Suggestions replace lines of the new file, so removed lines are left out
+        let lines: Vec<&DiffLine> = self
+            .diff_view
+            .selected_lines()
+            .iter()
+            .filter(|l| matches!(l.line_type, LineType::Addition | LineType::Context))
+            .collect();
+
+        let first = lines.first()?.new_line_no?;
+        let last = lines.last()?.new_line_no?;
+        let commit_id = self.current_commit_sha()?;
+
+        let original = lines
+            .iter()
+            .map(|l| l.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let target = CommentTarget {
+            path: file.filename.clone(),
+            line: last,
+            side: DiffSide::Right,
+            start_line: (first != last).then_some(first),
+            start_side: (first != last).then_some(DiffSide::Right),
+            commit_id,
+            reply_to: None,
+        };
+        Some((target, original))
+    }
+
     /// Pre-fetch commit files in parallel for faster navigation
     async fn prefetch_commit_files_parallel(&mut self, max_parallel: usize) -> Result<()> {
         let commits_to_fetch: Vec<_> = self
@@ -492,7 +1296,7 @@ impl App {
         }
 
         // Prepare data for parallel fetching
-        let client = self.client.clone();
+        let forge = Arc::clone(&self.forge);
         let owner = self.owner.clone();
         let repo = self.repo.clone();
         let pr_files = self.pr_files.clone();
@@ -501,7 +1305,7 @@ impl App {
         let futures: Vec<_> = commits_to_fetch
             .into_iter()
             .map(|(idx, commit)| {
-                let client = client.clone();
+                let forge = Arc::clone(&forge);
                 let owner = owner.clone();
                 let repo = repo.clone();
                 let sha = commit.sha.clone();
@@ -516,7 +1320,7 @@ impl App {
                         }
                     }
 
-                    match client.get_commit_files(&owner, &repo, &sha).await {
+                    match forge.get_commit_files(&owner, &repo, &sha).await {
                         Ok(files) => Ok((sha, files)),
                         Err(e) => {
                             eprintln!("Failed to pre-fetch commit {}: {}", &sha, e);
@@ -539,7 +1343,11 @@ impl App {
     }
 
     pub async fn handle_refresh(&mut self) -> Result<()> {
-        self.load_pr_data().await
+        self.load_pr_data().await?;
+        if self.conversation.loaded {
+            self.reload_timeline().await;
+        }
+        Ok(())
     }
 
     pub fn cycle_theme(&mut self) -> Result<()> {
@@ -548,7 +1356,27 @@ impl App {
         Ok(())
     }
 
+    /// The GitHub client, or an error message when the pull request is on another forge
+    fn require_github(&mut self) -> Option<GitHubClient> {
+        if self.github.is_none() {
+            self.status_message = Some(StatusMessage::error(
+                "Only available for GitHub pull requests",
+            ));
+        }
+        self.github.clone()
+    }
+
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
 }
+
+/// Line number and side a review comment on this diff line is anchored to.
+/// Removed lines are anchored to the old file, everything else to the new one.
+fn line_anchor(line: &DiffLine) -> Option<(usize, DiffSide)> {
+    match line.line_type {
+        LineType::Deletion => Some((line.old_line_no?, DiffSide::Left)),
+        LineType::Addition | LineType::Context => Some((line.new_line_no?, DiffSide::Right)),
+        LineType::Header => None,
+    }
+}
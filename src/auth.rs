@@ -10,20 +10,26 @@ struct AuthInfo {
     password: String,
 }
 
-/// Attempts to find GitHub token from multiple sources with priority ordering
-pub fn get_github_token(cli_token: Option<String>) -> Result<Option<String>> {
+/// Attempts to find an API token from multiple sources with priority ordering.
+/// `machine` is the API host looked up in ~/.authinfo, e.g. `api.github.com`,
+/// and `env_var` the environment variable checked last, e.g. `GITHUB_TOKEN`.
+pub fn get_token(
+    cli_token: Option<String>,
+    machine: &str,
+    env_var: &str,
+) -> Result<Option<String>> {
     // 1. First priority: Command-line argument
     if let Some(token) = cli_token {
         return Ok(Some(token));
     }
 
     // 2. Second priority: ~/.authinfo or ~/.netrc file
-    match read_authinfo_token() {
+    match read_authinfo_token(machine) {
         Ok(Some(token)) => return Ok(Some(token)),
         Ok(None) => {
             // File exists but no matching entry found
             if std::env::var("REVU_DEBUG").is_ok() {
-                eprintln!("Debug: authinfo file found but no entry for machine api.github.com with login ending in ^revu");
+                eprintln!("Debug: authinfo file found but no entry for machine {machine}");
             }
         }
         Err(e) => {
@@ -34,8 +40,8 @@ pub fn get_github_token(cli_token: Option<String>) -> Result<Option<String>> {
         }
     }
 
-    // 3. Third priority: GITHUB_TOKEN environment variable
-    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+    // 3. Third priority: environment variable
+    if let Ok(token) = std::env::var(env_var) {
         return Ok(Some(token));
     }
 
@@ -43,8 +49,8 @@ pub fn get_github_token(cli_token: Option<String>) -> Result<Option<String>> {
 }
 
 /// Reads GitHub token from ~/.authinfo or ~/.netrc file
-/// Looks for entries matching: machine api.github.com login USERNAME password TOKEN
-fn read_authinfo_token() -> Result<Option<String>> {
+/// Looks for entries matching: machine MACHINE login USERNAME password TOKEN
+fn read_authinfo_token(machine: &str) -> Result<Option<String>> {
     // Try ~/.authinfo first, then ~/.netrc
     let home = std::env::var("HOME").context("HOME environment variable not set")?;
     let paths = vec![
@@ -82,15 +88,15 @@ fn read_authinfo_token() -> Result<Option<String>> {
         // Parse all entries and find the one we want
         let entries = parse_all_authinfo(&contents)?;
         for auth in entries {
-            // Just look for api.github.com, regardless of login suffix
-            if auth.machine == "api.github.com" {
+            // Just look for the API host, regardless of login suffix
+            if auth.machine.eq_ignore_ascii_case(machine) {
                 return Ok(Some(auth.password));
             }
         }
 
         if std::env::var("REVU_DEBUG").is_ok() {
             eprintln!(
-                "Debug: Found {} entries in {}, but none match api.github.com",
+                "Debug: Found {} entries in {}, but none match {machine}",
                 parse_all_authinfo(&contents)?.len(),
                 path.display()
             );
@@ -1,9 +1,21 @@
-use crate::github::models::DiffContent;
+use crate::{
+    github::models::{DiffContent, ReviewThread},
+    settings::CacheSettings,
+};
 use lru::LruCache;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::sync::RwLock;
 
+/// Version of the on-disk format, bumped when cached values change shape
+const DISK_FORMAT: &str = "v1";
+
 /// Cache key for file content
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct FileCacheKey {
@@ -13,6 +25,28 @@ pub struct FileCacheKey {
     pub sha: String,
 }
 
+impl FileCacheKey {
+    /// Key of a file by its git blob SHA, shared by every commit and path with the same content
+    pub fn blob(owner: &str, repo: &str, sha: &str) -> Self {
+        Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            path: String::new(),
+            sha: sha.to_string(),
+        }
+    }
+
+    /// Key on disk, if the content can be kept. Only content at a commit SHA never changes.
+    fn disk_key(&self) -> Option<String> {
+        is_commit_sha(&self.sha).then(|| {
+            format!(
+                "file:{}/{}@{}:{}",
+                self.owner, self.repo, self.sha, self.path
+            )
+        })
+    }
+}
+
 /// Cache key for diff content (base_sha -> head_sha for a file)
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct DiffCacheKey {
@@ -23,9 +57,27 @@ pub struct DiffCacheKey {
     pub head_sha: String,
 }
 
-/// Thread-safe LRU cache for file contents
+impl DiffCacheKey {
+    /// Key on disk, if the diff can be kept. Working tree changes have no commit SHA.
+    fn disk_key(&self) -> Option<String> {
+        (is_commit_sha(&self.base_sha) && is_commit_sha(&self.head_sha)).then(|| {
+            format!(
+                "diff:{}/{}@{}..{}:{}",
+                self.owner, self.repo, self.base_sha, self.head_sha, self.path
+            )
+        })
+    }
+}
+
+/// Whether a ref is a full commit SHA (SHA-1 or SHA-256), rather than a branch or a pseudo-commit
+fn is_commit_sha(r#ref: &str) -> bool {
+    matches!(r#ref.len(), 40 | 64) && r#ref.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Thread-safe LRU cache for file contents, backed by the disk cache if there is one
 pub struct FileContentCache {
     cache: Arc<RwLock<LruCache<FileCacheKey, String>>>,
+    disk: Option<DiskCache>,
 }
 
 impl FileContentCache {
@@ -34,17 +86,34 @@ impl FileContentCache {
         let cap = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::new(100).unwrap());
         Self {
             cache: Arc::new(RwLock::new(LruCache::new(cap))),
+            disk: None,
+        }
+    }
+
+    /// Create a new cache that also keeps files on disk between runs
+    pub fn persistent(capacity: usize, disk: Option<DiskCache>) -> Self {
+        Self {
+            disk,
+            ..Self::new(capacity)
         }
     }
 
     /// Get a file from the cache
     pub async fn get(&self, key: &FileCacheKey) -> Option<String> {
-        let mut cache = self.cache.write().await;
-        cache.get(key).cloned()
+        if let Some(content) = self.cache.write().await.get(key).cloned() {
+            return Some(content);
+        }
+
+        let content: String = self.disk.as_ref()?.get(&key.disk_key()?).await?;
+        self.cache.write().await.put(key.clone(), content.clone());
+        Some(content)
     }
 
     /// Put a file in the cache
     pub async fn put(&self, key: FileCacheKey, content: String) {
+        if let (Some(disk), Some(disk_key)) = (&self.disk, key.disk_key()) {
+            disk.put(&disk_key, &content).await;
+        }
         let mut cache = self.cache.write().await;
         cache.put(key, content);
     }
@@ -61,6 +130,7 @@ impl Clone for FileContentCache {
     fn clone(&self) -> Self {
         Self {
             cache: Arc::clone(&self.cache),
+            disk: self.disk.clone(),
         }
     }
 }
@@ -71,9 +141,10 @@ impl Default for FileContentCache {
     }
 }
 
-/// Thread-safe LRU cache for diff contents
+/// Thread-safe LRU cache for diff contents, backed by the disk cache if there is one
 pub struct DiffCache {
     cache: Arc<RwLock<LruCache<DiffCacheKey, DiffContent>>>,
+    disk: Option<DiskCache>,
 }
 
 impl DiffCache {
@@ -82,17 +153,34 @@ impl DiffCache {
         let cap = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::new(50).unwrap());
         Self {
             cache: Arc::new(RwLock::new(LruCache::new(cap))),
+            disk: None,
+        }
+    }
+
+    /// Create a new cache that also keeps diffs on disk between runs
+    pub fn persistent(capacity: usize, disk: Option<DiskCache>) -> Self {
+        Self {
+            disk,
+            ..Self::new(capacity)
         }
     }
 
     /// Get a diff from the cache
     pub async fn get(&self, key: &DiffCacheKey) -> Option<DiffContent> {
-        let mut cache = self.cache.write().await;
-        cache.get(key).cloned()
+        if let Some(content) = self.cache.write().await.get(key).cloned() {
+            return Some(content);
+        }
+
+        let content: DiffContent = self.disk.as_ref()?.get(&key.disk_key()?).await?;
+        self.cache.write().await.put(key.clone(), content.clone());
+        Some(content)
     }
 
     /// Put a diff in the cache
     pub async fn put(&self, key: DiffCacheKey, content: DiffContent) {
+        if let (Some(disk), Some(disk_key)) = (&self.disk, key.disk_key()) {
+            disk.put(&disk_key, &content).await;
+        }
         let mut cache = self.cache.write().await;
         cache.put(key, content);
     }
@@ -109,6 +197,7 @@ impl Clone for DiffCache {
     fn clone(&self) -> Self {
         Self {
             cache: Arc::clone(&self.cache),
+            disk: self.disk.clone(),
         }
     }
 }
@@ -118,3 +207,375 @@ impl Default for DiffCache {
         Self::new(50)
     }
 }
+
+/// An API response with the validators to check whether it's still current
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// `Link` header, pointing to the other pages of a list
+    #[serde(default)]
+    pub link: Option<String>,
+    pub body: String,
+}
+
+/// Thread-safe LRU cache of API responses by URL, for conditional requests.
+/// Responses are revalidated before they're used, so unlike file contents they can be kept on
+/// disk even when they change.
+pub struct ResponseCache {
+    cache: Arc<RwLock<LruCache<String, CachedResponse>>>,
+    disk: Option<DiskCache>,
+}
+
+impl ResponseCache {
+    /// Create a new cache with the specified capacity, backed by the disk cache if there is one
+    pub fn new(capacity: usize, disk: Option<DiskCache>) -> Self {
+        let cap = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::new(500).unwrap());
+        Self {
+            cache: Arc::new(RwLock::new(LruCache::new(cap))),
+            disk,
+        }
+    }
+
+    /// Get the last response from a URL
+    pub async fn get(&self, url: &str) -> Option<CachedResponse> {
+        if let Some(response) = self.cache.write().await.get(url).cloned() {
+            return Some(response);
+        }
+
+        let response: CachedResponse = self.disk.as_ref()?.get(&response_key(url)).await?;
+        self.cache
+            .write()
+            .await
+            .put(url.to_string(), response.clone());
+        Some(response)
+    }
+
+    /// Put the response from a URL in the cache, and on disk too if it's `persistent`
+    pub async fn put(&self, url: String, response: CachedResponse, persistent: bool) {
+        if let (true, Some(disk)) = (persistent, &self.disk) {
+            disk.put(&response_key(&url), &response).await;
+        }
+        let mut cache = self.cache.write().await;
+        cache.put(url, response);
+    }
+}
+
+fn response_key(url: &str) -> String {
+    format!("response:{url}")
+}
+
+impl Clone for ResponseCache {
+    fn clone(&self) -> Self {
+        Self {
+            cache: Arc::clone(&self.cache),
+            disk: self.disk.clone(),
+        }
+    }
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self::new(500, None)
+    }
+}
+
+/// Cache of file contents and diffs kept on disk between runs, under `$XDG_CACHE_HOME/revu`.
+/// When it grows past its size limit, the least recently used entries are removed.
+/// It's only an optimization, so failing to read or write it is ignored.
+#[derive(Clone)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_size: u64,
+    /// Bytes used on disk, as of the last eviction plus what was written since
+    used: Arc<AtomicU64>,
+    evicting: Arc<AtomicBool>,
+}
+
+/// A cached value with its full key, since entries are stored under a hash of the key
+#[derive(Serialize, Deserialize)]
+struct DiskEntry<T> {
+    key: String,
+    value: T,
+}
+
+impl DiskCache {
+    /// Open the cache configured in the settings, `None` if it's disabled or can't be created.
+    /// Old entries are evicted in the background if the cache is over its limit.
+    pub fn open(settings: &CacheSettings) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        let dir = settings.dir.clone().unwrap_or_else(default_cache_dir);
+        Self::at(dir.join(DISK_FORMAT), settings.max_size_mb * 1024 * 1024)
+    }
+
+    fn at(dir: PathBuf, max_size: u64) -> Option<Self> {
+        fs::create_dir_all(&dir).ok()?;
+        let cache = Self {
+            dir,
+            max_size,
+            used: Arc::new(AtomicU64::new(0)),
+            // The first eviction also works out how much of the limit is in use
+            evicting: Arc::new(AtomicBool::new(true)),
+        };
+        cache.spawn_eviction();
+        Some(cache)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let path = self.entry_path(key);
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        let entry: DiskEntry<T> = serde_json::from_slice(&bytes).ok()?;
+        if entry.key != key {
+            return None;
+        }
+
+        // The modification time orders entries for eviction
+        if let Ok(file) = tokio::fs::File::options().append(true).open(&path).await {
+            let _ = file.into_std().await.set_modified(SystemTime::now());
+        }
+        Some(entry.value)
+    }
+
+    async fn put<T: Serialize>(&self, key: &str, value: &T) {
+        let entry = DiskEntry {
+            key: key.to_string(),
+            value,
+        };
+        let Ok(bytes) = serde_json::to_vec(&entry) else {
+            return;
+        };
+
+        // Write to a temporary file first, so readers never see half an entry
+        let path = self.entry_path(key);
+        let temp = path.with_extension("tmp");
+        let written = async {
+            tokio::fs::create_dir_all(path.parent()?).await.ok()?;
+            tokio::fs::write(&temp, &bytes).await.ok()?;
+            tokio::fs::rename(&temp, &path).await.ok()
+        };
+        if written.await.is_none() {
+            return;
+        }
+
+        let used = self.used.fetch_add(bytes.len() as u64, Ordering::Relaxed) + bytes.len() as u64;
+        if used > self.max_size && !self.evicting.swap(true, Ordering::Relaxed) {
+            self.spawn_eviction();
+        }
+    }
+
+    /// Entries are spread over subdirectories by the first byte of their hash
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let hash = format!("{:016x}", fnv1a(key.as_bytes()));
+        self.dir
+            .join(&hash[..2])
+            .join(format!("{}.json", &hash[2..]))
+    }
+
+    fn spawn_eviction(&self) {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let used = evict(&cache.dir, cache.max_size);
+            cache.used.store(used, Ordering::Relaxed);
+            cache.evicting.store(false, Ordering::Relaxed);
+        });
+    }
+}
+
+/// 64-bit FNV-1a hash. Unlike the standard library's hasher, it stays the same across Rust
+/// releases, so entries written by one build of revu are found by the next.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Remove the least recently used entries until the cache is well under `max_size`,
+/// so that it isn't evicted again right away. Returns the bytes left in use.
+fn evict(dir: &Path, max_size: u64) -> u64 {
+    let mut entries: Vec<(SystemTime, u64, PathBuf)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|subdir| fs::read_dir(subdir.path()).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+        })
+        .collect();
+    let mut used: u64 = entries.iter().map(|(_, size, _)| size).sum();
+    if used <= max_size {
+        return used;
+    }
+
+    entries.sort();
+    let target = max_size / 10 * 8;
+    for (_, size, path) in entries {
+        if used <= target {
+            break;
+        }
+        if fs::remove_file(path).is_ok() {
+            used -= size;
+        }
+    }
+    used
+}
+
+/// `$XDG_CACHE_HOME/revu`, or `~/.cache/revu`
+fn default_cache_dir() -> PathBuf {
+    let cache_dir = if let Ok(xdg_cache) = std::env::var("XDG_CACHE_HOME") {
+        PathBuf::from(xdg_cache)
+    } else if let Ok(home) = std::env::var("HOME") {
+        PathBuf::from(home).join(".cache")
+    } else {
+        std::env::temp_dir()
+    };
+
+    cache_dir.join("revu")
+}
+
+/// Review comment threads indexed by file path and anchor line
+#[derive(Debug, Clone, Default)]
+pub struct ReviewThreadCache {
+    threads: HashMap<String, BTreeMap<usize, Vec<ReviewThread>>>,
+}
+
+impl ReviewThreadCache {
+    pub fn new(threads: Vec<ReviewThread>) -> Self {
+        let mut cache = Self::default();
+        for thread in threads {
+            // Outdated threads have no current line, fall back to where they were made
+            let root = thread.root();
+            let line = root.line.or(root.original_line).unwrap_or(0);
+            cache
+                .threads
+                .entry(thread.path().to_string())
+                .or_default()
+                .entry(line)
+                .or_default()
+                .push(thread);
+        }
+        cache
+    }
+
+    /// All threads on a file, ordered by line
+    pub fn for_file(&self, path: &str) -> Vec<ReviewThread> {
+        self.threads
+            .get(path)
+            .map(|lines| lines.values().flatten().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Total number of threads across all files
+    pub fn len(&self) -> usize {
+        self.threads
+            .values()
+            .flat_map(|lines| lines.values())
+            .map(Vec::len)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_key(sha: &str) -> FileCacheKey {
+        FileCacheKey {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            path: "src/lib.rs".to_string(),
+            sha: sha.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entry_paths_are_stable() {
+        // Reference values of 64-bit FNV-1a
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::at(dir.path().to_path_buf(), 1024).unwrap();
+        assert_eq!(
+            cache.entry_path("a"),
+            dir.path().join("af").join("63dc4c8601ec8c.json")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_outlives_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = DiskCache::at(dir.path().to_path_buf(), 1024 * 1024);
+        let sha = "a".repeat(40);
+
+        let cache = FileContentCache::persistent(10, disk.clone());
+        cache.put(file_key(&sha), "fn a() {}".to_string()).await;
+        // Pseudo-commits of the working tree change, so they stay in memory only
+        cache
+            .put(file_key("unstaged"), "fn b() {}".to_string())
+            .await;
+
+        // A new run starts with an empty memory cache
+        let cache = FileContentCache::persistent(10, disk);
+        assert_eq!(
+            cache.get(&file_key(&sha)).await.as_deref(),
+            Some("fn a() {}")
+        );
+        assert_eq!(cache.get(&file_key("unstaged")).await, None);
+        assert_eq!(cache.get(&file_key(&"b".repeat(40))).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_only_persistent_responses_outlive_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let disk = DiskCache::at(dir.path().to_path_buf(), 1024 * 1024);
+        let response = |body: &str| CachedResponse {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            link: None,
+            body: body.to_string(),
+        };
+
+        let cache = ResponseCache::new(10, disk.clone());
+        cache.put("pulls/1".to_string(), response("{}"), true).await;
+        cache
+            .put("pulls/1/comments".to_string(), response("[]"), false)
+            .await;
+        assert!(cache.get("pulls/1/comments").await.is_some());
+
+        let cache = ResponseCache::new(10, disk);
+        assert_eq!(cache.get("pulls/1").await.unwrap().body, "{}");
+        assert!(cache.get("pulls/1/comments").await.is_none());
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let subdir = dir.path().join("ab");
+        fs::create_dir(&subdir).unwrap();
+        let now = SystemTime::now();
+        for (i, name) in ["old", "newer", "newest"].iter().enumerate() {
+            let path = subdir.join(name);
+            fs::write(&path, [0; 100]).unwrap();
+            let modified = now - std::time::Duration::from_secs(60 * (3 - i as u64));
+            fs::File::options()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+
+        assert_eq!(evict(dir.path(), 300), 300);
+        assert_eq!(evict(dir.path(), 250), 200);
+        assert!(!subdir.join("old").exists());
+        assert!(subdir.join("newest").exists());
+    }
+}
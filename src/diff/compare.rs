@@ -0,0 +1,220 @@
+use crate::{
+    diff::DiffParser,
+    github::models::{FileChange, FileStatus, LineType},
+};
+use anyhow::{Context, Result};
+use similar::TextDiff;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Compare two local files, or two directories file by file.
+/// Directories list the files that were added, removed or modified, in path order.
+pub fn compare_paths(old: &Path, new: &Path) -> Result<Vec<FileChange>> {
+    match (old.is_dir(), new.is_dir()) {
+        (true, true) => compare_dirs(old, new),
+        (false, false) => {
+            // `git difftool` passes /dev/null for the missing side of an added or deleted file
+            let name = if is_dev_null(new) { old } else { new };
+            let file = compare_file(&name.to_string_lossy(), read_file(old)?, read_file(new)?)?;
+            Ok(file.into_iter().collect())
+        }
+        _ => anyhow::bail!("Can't compare a file with a directory"),
+    }
+}
+
+fn compare_dirs(old: &Path, new: &Path) -> Result<Vec<FileChange>> {
+    let old_files = list_files(old)?;
+    let new_files = list_files(new)?;
+
+    let mut files = Vec::new();
+    for path in old_files.union(&new_files) {
+        let read = |root: &Path, listed: &BTreeSet<PathBuf>| match listed.contains(path) {
+            true => read_entry(&root.join(path)),
+            false => Ok(None),
+        };
+        let (old_content, new_content) = (read(old, &old_files)?, read(new, &new_files)?);
+
+        files.extend(compare_file(
+            &path.to_string_lossy(),
+            old_content,
+            new_content,
+        )?);
+    }
+    Ok(files)
+}
+
+/// Diff one file's contents, `None` for the side where it doesn't exist.
+/// Files that are the same on both sides are left out.
+fn compare_file(
+    filename: &str,
+    old: Option<Vec<u8>>,
+    new: Option<Vec<u8>>,
+) -> Result<Option<FileChange>> {
+    let status = match (&old, &new) {
+        (None, Some(_)) => FileStatus::Added,
+        (Some(_), None) => FileStatus::Deleted,
+        (Some(old), Some(new)) if old != new => FileStatus::Modified,
+        _ => return Ok(None),
+    };
+    let old = text(old);
+    let new = text(new);
+
+    let patch = TextDiff::from_lines(&old, &new)
+        .unified_diff()
+        .context_radius(3)
+        .to_string();
+    let diff_content = DiffParser::create_full_file_diff(&old, &new, &patch)?;
+    let count = |line_type: LineType| {
+        diff_content
+            .full_file_view
+            .iter()
+            .filter(|l| l.line_type == line_type)
+            .count() as u32
+    };
+
+    Ok(Some(FileChange {
+        filename: filename.to_string(),
+        status,
+        additions: count(LineType::Addition),
+        deletions: count(LineType::Deletion),
+        patch: Some(patch).filter(|p| !p.is_empty()),
+        raw_content: Some(new),
+        diff_content: Some(diff_content),
+    }))
+}
+
+/// Contents of a file to show in the diff. Binary files are shown as empty.
+pub(crate) fn text(content: Option<Vec<u8>>) -> String {
+    match content {
+        Some(bytes) if !bytes.contains(&0) => String::from_utf8_lossy(&bytes).into_owned(),
+        _ => String::new(),
+    }
+}
+
+fn read_file(path: &Path) -> Result<Option<Vec<u8>>> {
+    if is_dev_null(path) {
+        return Ok(None);
+    }
+    let content = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(Some(content))
+}
+
+/// Contents of a file in a compared directory. Like git, symlinks are compared by their target
+/// rather than followed, which could lead out of the directory or back into it.
+fn read_entry(path: &Path) -> Result<Option<Vec<u8>>> {
+    let metadata =
+        fs::symlink_metadata(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if !metadata.is_symlink() {
+        return read_file(path);
+    }
+    let target =
+        fs::read_link(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(Some(target.to_string_lossy().into_owned().into_bytes()))
+}
+
+fn is_dev_null(path: &Path) -> bool {
+    path == Path::new("/dev/null")
+}
+
+/// Paths of all files under `root`, relative to it. Git's own metadata isn't compared.
+/// Symlinks count as files, even when they point to a directory.
+fn list_files(root: &Path) -> Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    let mut dirs = vec![PathBuf::new()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = fs::read_dir(root.join(&dir))
+            .with_context(|| format!("Failed to read {}", root.join(&dir).display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let path = dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                if entry.file_name() != ".git" {
+                    dirs.push(path);
+                }
+            } else {
+                files.insert(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_dirs() {
+        let old = tempfile::tempdir().unwrap();
+        let new = tempfile::tempdir().unwrap();
+        for dir in [old.path(), new.path()] {
+            fs::create_dir_all(dir.join("src")).unwrap();
+            fs::write(dir.join("README.md"), "same\n").unwrap();
+        }
+        fs::write(old.path().join("src/lib.rs"), "fn a() {}\n").unwrap();
+        fs::write(new.path().join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        fs::write(old.path().join("removed.txt"), "gone\n").unwrap();
+        fs::write(new.path().join("src/added.rs"), "fn c() {}\n").unwrap();
+
+        let files = compare_paths(old.path(), new.path()).unwrap();
+        let summary: Vec<(&str, FileStatus, u32, u32)> = files
+            .iter()
+            .map(|f| {
+                (
+                    f.filename.as_str(),
+                    f.status.clone(),
+                    f.additions,
+                    f.deletions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("removed.txt", FileStatus::Deleted, 0, 1),
+                ("src/added.rs", FileStatus::Added, 1, 0),
+                ("src/lib.rs", FileStatus::Modified, 1, 0),
+            ]
+        );
+        assert!(files[2]
+            .patch
+            .as_deref()
+            .unwrap()
+            .starts_with("@@ -1 +1,2 @@"));
+        assert_eq!(files[2].diff_content.as_ref().unwrap().hunks.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_not_followed() {
+        let old = tempfile::tempdir().unwrap();
+        let new = tempfile::tempdir().unwrap();
+        // A link back to its own directory would be listed forever if it were followed
+        std::os::unix::fs::symlink(".", new.path().join("loop")).unwrap();
+
+        let files = compare_paths(old.path(), new.path()).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename, "loop");
+        assert_eq!(files[0].status, FileStatus::Added);
+        assert_eq!(files[0].raw_content.as_deref(), Some("."));
+    }
+
+    #[test]
+    fn test_compare_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        fs::write(&file, "hello\n").unwrap();
+
+        let files = compare_paths(Path::new("/dev/null"), &file).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].status, FileStatus::Added);
+        assert!(files[0].filename.ends_with("notes.txt"));
+
+        assert!(compare_paths(&file, &file).unwrap().is_empty());
+        assert!(compare_paths(&file, dir.path()).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod compare;
 pub mod parser;
 
 pub use parser::DiffParser;
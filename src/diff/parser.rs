@@ -1,5 +1,8 @@
-use crate::github::models::{DiffContent, DiffHunk, DiffLine, FileChange, FileStatus, LineType};
-use anyhow::Result;
+use crate::{
+    forge::Forge,
+    github::models::{DiffContent, DiffHunk, DiffLine, FileChange, FileStatus, LineType},
+};
+use anyhow::{Context, Result};
 use futures::stream::{self, StreamExt};
 use regex::Regex;
 use std::sync::Arc;
@@ -53,6 +56,9 @@ impl DiffParser {
                         Some(old_line_no),
                         None,
                     )
+                } else if line.starts_with('\\') {
+                    // "\ No newline at end of file" isn't a line of the file
+                    continue;
                 } else if line.starts_with(' ') {
                     old_line_no += 1;
                     new_line_no += 1;
@@ -97,6 +103,87 @@ impl DiffParser {
         })
     }
 
+    /// Split a multi-file unified diff, as printed by `git diff` or `diff -u`, into its files.
+    /// Each file's hunks are parsed and shown as they are, without fetching any contents.
+    pub fn split_unified_diff(diff: &str) -> Result<Vec<FileChange>> {
+        let hunk_header_re = Regex::new(r"^@@ -\d+(?:,(\d+))? \+\d+(?:,(\d+))? @@")?;
+        let mut files = Vec::new();
+        let mut current: Option<PatchFile> = None;
+        // Lines still to come in the current hunk, on the old and the new side
+        let (mut old_left, mut new_left) = (0usize, 0usize);
+        let mut lines = diff.lines().peekable();
+
+        while let Some(line) = lines.next() {
+            if old_left > 0 || new_left > 0 {
+                match line.chars().next() {
+                    Some('+') => new_left = new_left.saturating_sub(1),
+                    Some('-') => old_left = old_left.saturating_sub(1),
+                    Some('\\') => {}
+                    _ => {
+                        old_left = old_left.saturating_sub(1);
+                        new_left = new_left.saturating_sub(1);
+                    }
+                }
+                if let Some(ref mut file) = current {
+                    file.push_hunk_line(line);
+                }
+                continue;
+            }
+
+            if let Some(caps) = hunk_header_re.captures(line) {
+                // A missing count means a single line
+                let count = |i| caps.get(i).map_or(Ok(1), |m| m.as_str().parse());
+                old_left = count(1)?;
+                new_left = count(2)?;
+                if let Some(ref mut file) = current {
+                    file.push_hunk_line(line);
+                }
+                continue;
+            }
+
+            if let Some(paths) = line.strip_prefix("diff --git ") {
+                files.extend(current.take().map(PatchFile::finish).transpose()?.flatten());
+                current = Some(PatchFile::from_git_header(paths));
+                continue;
+            }
+
+            if let Some(old_path) = line.strip_prefix("--- ") {
+                if let Some(new_path) = lines.peek().and_then(|l| l.strip_prefix("+++ ")) {
+                    // Plain `diff -u` output starts each file at its `---` line
+                    if current.as_ref().is_none_or(|f| !f.hunks.is_empty()) {
+                        files.extend(current.take().map(PatchFile::finish).transpose()?.flatten());
+                        current = Some(PatchFile::default());
+                    }
+                    if let Some(ref mut file) = current {
+                        file.old_path = diff_path(old_path, "a/");
+                        file.new_path = diff_path(new_path, "b/");
+                    }
+                    lines.next();
+                    continue;
+                }
+            }
+
+            // Anything before the first file, like a commit message, is skipped
+            let Some(ref mut file) = current else {
+                continue;
+            };
+            if line.starts_with("new file mode") {
+                file.status = Some(FileStatus::Added);
+            } else if line.starts_with("deleted file mode") {
+                file.status = Some(FileStatus::Deleted);
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                file.status = Some(FileStatus::Renamed);
+                file.new_path = Some(path.to_string());
+            } else if let Some(path) = line.strip_prefix("copy to ") {
+                file.status = Some(FileStatus::Copied);
+                file.new_path = Some(path.to_string());
+            }
+        }
+
+        files.extend(current.map(PatchFile::finish).transpose()?.flatten());
+        Ok(files)
+    }
+
     /// Creates a full file view with inline diff annotations
     pub fn create_full_file_diff(
         old_content: &str,
@@ -168,7 +255,7 @@ impl DiffParser {
     /// Enrich a single file with diff content
     pub async fn enrich_single_file(
         file: &mut FileChange,
-        client: &crate::github::GitHubClient,
+        forge: &dyn Forge,
         owner: &str,
         repo: &str,
         base_ref: &str,
@@ -176,19 +263,17 @@ impl DiffParser {
     ) -> Result<()> {
         // Get file content from both refs
         let old_content = if file.status != FileStatus::Added {
-            client
+            forge
                 .get_file_content(owner, repo, &file.filename, base_ref)
-                .await
-                .unwrap_or_default()
+                .await?
         } else {
             String::new()
         };
 
         let new_content = if file.status != FileStatus::Deleted {
-            client
+            forge
                 .get_file_content(owner, repo, &file.filename, head_ref)
-                .await
-                .unwrap_or_default()
+                .await?
         } else {
             String::new()
         };
@@ -206,9 +291,10 @@ impl DiffParser {
         Ok(())
     }
 
+    /// Fetch contents and build the full file view of files that don't have a diff yet
     pub async fn enrich_file_changes(
         files: &mut [FileChange],
-        client: &crate::github::GitHubClient,
+        forge: &Arc<dyn Forge>,
         owner: &str,
         repo: &str,
         base_ref: &str,
@@ -216,14 +302,19 @@ impl DiffParser {
     ) -> Result<()> {
         // Process files in batches of 10 to respect rate limits
         const BATCH_SIZE: usize = 10;
-        let client = Arc::new(client.clone());
 
-        for chunk in files.chunks_mut(BATCH_SIZE) {
+        // Files read from a patch come with their diff already parsed
+        let mut pending: Vec<&mut FileChange> = files
+            .iter_mut()
+            .filter(|file| file.diff_content.is_none())
+            .collect();
+
+        for chunk in pending.chunks_mut(BATCH_SIZE) {
             // Create futures for fetching file contents in parallel
             let futures: Vec<_> = chunk
                 .iter()
                 .map(|file| {
-                    let client = Arc::clone(&client);
+                    let forge = Arc::clone(forge);
                     let owner = owner.to_string();
                     let repo = repo.to_string();
                     let filename = file.filename.clone();
@@ -232,49 +323,49 @@ impl DiffParser {
                     let head_ref = head_ref.to_string();
 
                     async move {
+                        let fetch = |r#ref| forge.get_file_content(&owner, &repo, &filename, r#ref);
+
                         // Fetch old content if not a new file
                         let old_content_future = if status != FileStatus::Added {
-                            Some(client.get_file_content(&owner, &repo, &filename, &base_ref))
+                            Some(fetch(&base_ref))
                         } else {
                             None
                         };
 
                         // Fetch new content if not a deleted file
                         let new_content_future = if status != FileStatus::Deleted {
-                            Some(client.get_file_content(&owner, &repo, &filename, &head_ref))
+                            Some(fetch(&head_ref))
                         } else {
                             None
                         };
 
                         // Execute both futures concurrently
-                        let (old_content, new_content) =
-                            match (old_content_future, new_content_future) {
-                                (Some(old_fut), Some(new_fut)) => {
-                                    let (old, new) = tokio::join!(old_fut, new_fut);
-                                    (old.unwrap_or_default(), new.unwrap_or_default())
-                                }
-                                (Some(old_fut), None) => {
-                                    (old_fut.await.unwrap_or_default(), String::new())
-                                }
-                                (None, Some(new_fut)) => {
-                                    (String::new(), new_fut.await.unwrap_or_default())
-                                }
-                                (None, None) => (String::new(), String::new()),
-                            };
-
-                        (old_content, new_content)
+                        let contents = match (old_content_future, new_content_future) {
+                            (Some(old_fut), Some(new_fut)) => {
+                                let (old, new) = tokio::join!(old_fut, new_fut);
+                                (old?, new?)
+                            }
+                            (Some(old_fut), None) => (old_fut.await?, String::new()),
+                            (None, Some(new_fut)) => (String::new(), new_fut.await?),
+                            (None, None) => (String::new(), String::new()),
+                        };
+
+                        anyhow::Ok(contents)
                     }
                 })
                 .collect();
 
-            // Execute all futures in parallel and collect results
-            let results: Vec<(String, String)> = stream::iter(futures)
-                .buffer_unordered(BATCH_SIZE)
-                .collect()
-                .await;
+            // Execute all futures in parallel, keeping results in the order of the files
+            let results: Vec<Result<(String, String)>> =
+                stream::iter(futures).buffered(BATCH_SIZE).collect().await;
+
+            // Apply results to the files. Files whose contents couldn't be fetched are left
+            // without a diff, so that the error is reported when they're opened.
+            for (file, result) in chunk.iter_mut().zip(results) {
+                let Ok((old_content, new_content)) = result else {
+                    continue;
+                };
 
-            // Apply results to the files
-            for (file, (old_content, new_content)) in chunk.iter_mut().zip(results) {
                 // Generate full file diff view
                 let diff_content = if let Some(ref patch) = file.patch {
                     Self::create_full_file_diff(&old_content, &new_content, patch)?
@@ -291,6 +382,91 @@ impl DiffParser {
     }
 }
 
+/// A file of a multi-file unified diff, while it's being read
+#[derive(Default)]
+struct PatchFile {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    /// Set by git's extended headers, otherwise worked out from the paths
+    status: Option<FileStatus>,
+    hunks: String,
+}
+
+impl PatchFile {
+    /// Start a file at a `diff --git a/old b/new` line
+    fn from_git_header(paths: &str) -> Self {
+        let (old_path, new_path) = match paths.rsplit_once(" b/") {
+            Some((old, new)) => (
+                old.strip_prefix("a/").unwrap_or(old).to_string(),
+                new.to_string(),
+            ),
+            None => (paths.to_string(), paths.to_string()),
+        };
+
+        Self {
+            old_path: Some(old_path),
+            new_path: Some(new_path),
+            ..Self::default()
+        }
+    }
+
+    fn push_hunk_line(&mut self, line: &str) {
+        self.hunks.push_str(line);
+        self.hunks.push('\n');
+    }
+
+    /// The file change, or nothing if no path was found for it
+    fn finish(self) -> Result<Option<FileChange>> {
+        let status = self
+            .status
+            .unwrap_or(match (&self.old_path, &self.new_path) {
+                (None, _) => FileStatus::Added,
+                (_, None) => FileStatus::Deleted,
+                (Some(old), Some(new)) if old != new => FileStatus::Renamed,
+                _ => FileStatus::Modified,
+            });
+        // Deleted files only have the old path
+        let Some(filename) = self.new_path.or(self.old_path) else {
+            return Ok(None);
+        };
+
+        let mut diff_content = DiffParser::parse_unified_diff(&self.hunks)
+            .with_context(|| format!("Failed to parse the diff of {filename}"))?;
+        diff_content.full_file_view = diff_content
+            .hunks
+            .iter()
+            .flat_map(|hunk| hunk.lines.iter().cloned())
+            .collect();
+        let count = |line_type: LineType| {
+            diff_content
+                .full_file_view
+                .iter()
+                .filter(|l| l.line_type == line_type)
+                .count() as u32
+        };
+
+        Ok(Some(FileChange {
+            filename,
+            status,
+            additions: count(LineType::Addition),
+            deletions: count(LineType::Deletion),
+            patch: Some(self.hunks).filter(|h| !h.is_empty()),
+            raw_content: None,
+            diff_content: Some(diff_content),
+        }))
+    }
+}
+
+/// Path of a `---`/`+++` line, without its timestamp and git's `a/`/`b/` prefix.
+/// `/dev/null` stands for a file that doesn't exist on that side.
+fn diff_path(path: &str, prefix: &str) -> Option<String> {
+    let path = path.split('\t').next().unwrap_or(path).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -369,4 +545,56 @@ mod tests {
         // Should have 3 lines
         assert_eq!(result.full_file_view.len(), 3);
     }
+
+    #[test]
+    fn test_split_git_diff() {
+        let diff = "commit 1234\n\n    Message\n\
+diff --git a/src/lib.rs b/src/lib.rs\nindex 1..2 100644\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n\
+diff --git a/old.rs b/old.rs\ndeleted file mode 100644\n--- a/old.rs\n+++ /dev/null\n\
+@@ -1,2 +0,0 @@\n--- not a header\n-+++ either\n\\ No newline at end of file\n\
+diff --git a/a.rs b/b.rs\nsimilarity index 100%\nrename from a.rs\nrename to b.rs\n";
+
+        let files = DiffParser::split_unified_diff(diff).unwrap();
+        let names: Vec<&str> = files.iter().map(|f| f.filename.as_str()).collect();
+        assert_eq!(names, ["src/lib.rs", "old.rs", "b.rs"]);
+
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!((files[0].additions, files[0].deletions), (1, 0));
+        assert_eq!(
+            files[0].patch.as_deref(),
+            Some("@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n")
+        );
+        let view = &files[0].diff_content.as_ref().unwrap().full_file_view;
+        assert_eq!(view.len(), 3);
+        assert_eq!(view[0].line_type, LineType::Header);
+        assert_eq!(view[2].new_line_no, Some(2));
+
+        // Removed lines that look like headers stay part of the hunk
+        assert_eq!(files[1].status, FileStatus::Deleted);
+        assert_eq!((files[1].additions, files[1].deletions), (0, 2));
+
+        assert_eq!(files[2].status, FileStatus::Renamed);
+        assert!(files[2].patch.is_none());
+    }
+
+    #[test]
+    fn test_split_plain_diff() {
+        let diff = "--- notes.txt\t2024-05-01 10:00:00\n+++ notes.txt\t2024-05-02 10:00:00\n\
+@@ -1,2 +1,2 @@\n keep\n-old\n+new\n\
+--- /dev/null\n+++ added.txt\n@@ -0,0 +1 @@\n+hello\n";
+
+        let files = DiffParser::split_unified_diff(diff).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].filename, "notes.txt");
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+        assert_eq!(files[1].filename, "added.txt");
+        assert_eq!(files[1].status, FileStatus::Added);
+
+        // A file whose hunks can't be parsed fails the whole diff rather than going missing
+        let diff = "--- big.txt\n+++ big.txt\n@@ -99999999999999999999999 +1 @@\n-old\n+new\n";
+        let error = DiffParser::split_unified_diff(diff).unwrap_err();
+        assert!(format!("{error:#}").contains("big.txt"));
+    }
 }
@@ -55,6 +55,16 @@ pub enum Action {
     ExitSearch,
     NextMatch,
     PrevMatch,
+    AddComment,
+    SubmitReview,
+    ToggleOverview,
+    ToggleConversation,
+    ToggleChecks,
+    ToggleSelection,
+    SuggestChange,
+    ReplyThread,
+    ToggleResolved,
+    ToggleThread,
     #[allow(dead_code)] // Used in search mode directly, not through key mapping
     ExecuteSearch,
     #[allow(dead_code)] // Used in search mode directly, not through key mapping
@@ -0,0 +1,247 @@
+use super::{Forge, ForgeKind};
+use crate::github::models::*;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+/// Version of the bundle format, bumped when it changes in a way older versions can't read
+const BUNDLE_VERSION: u32 = 2;
+
+/// File contents fetched at the same time while exporting
+const CONCURRENT_FETCHES: usize = 10;
+
+/// A snapshot of a pull request with everything needed to review it offline: its commits, the
+/// files each of them changed and the contents of those files before and after
+#[derive(Serialize, Deserialize)]
+pub struct Bundle {
+    version: u32,
+    exported_at: DateTime<Utc>,
+    host: Option<String>,
+    owner: String,
+    repo: String,
+    pr: PullRequest,
+    commits: Vec<Commit>,
+    pr_files: Vec<FileChange>,
+    /// Files changed by each commit, by SHA
+    commit_files: BTreeMap<String, Vec<FileChange>>,
+    /// File contents by ref, then by path. `None` for a file that doesn't exist at the ref.
+    contents: BTreeMap<String, BTreeMap<String, Option<String>>>,
+}
+
+impl Bundle {
+    /// Fetch everything about a pull request that's shown while reviewing it
+    pub async fn export(forge: &dyn Forge, parsed: &ParsedPrUrl) -> Result<Self> {
+        let (owner, repo) = (parsed.owner.as_str(), parsed.repo.as_str());
+        let pr = forge.get_pull_request(owner, repo, parsed.number).await?;
+        let commits = forge.get_pr_commits(owner, repo, parsed.number).await?;
+        let pr_files = forge.get_pr_files(owner, repo, parsed.number).await?;
+
+        let mut commit_files = BTreeMap::new();
+        for commit in &commits {
+            let files = forge.get_commit_files(owner, repo, &commit.sha).await?;
+            commit_files.insert(commit.sha.clone(), files);
+        }
+
+        // Each commit is compared with the one before it, the first with the base branch.
+        // Files that don't exist on one side are recorded as missing rather than fetched.
+        let mut wanted = BTreeSet::new();
+        let mut missing = BTreeSet::new();
+        let mut want = |files: &[FileChange], base: &str, head: &str| {
+            for file in files {
+                let path = file.filename.clone();
+                match file.status {
+                    FileStatus::Added => missing.insert((base.to_string(), path.clone())),
+                    _ => wanted.insert((base.to_string(), path.clone())),
+                };
+                match file.status {
+                    FileStatus::Deleted => missing.insert((head.to_string(), path)),
+                    _ => wanted.insert((head.to_string(), path)),
+                };
+            }
+        };
+        want(&pr_files, &pr.base.sha, &pr.head.sha);
+        let mut base = pr.base.sha.as_str();
+        for commit in &commits {
+            want(&commit_files[&commit.sha], base, &commit.sha);
+            base = &commit.sha;
+        }
+
+        let fetched: Vec<(String, String, String)> = stream::iter(wanted)
+            .map(|(r#ref, path)| async move {
+                let content = forge
+                    .get_file_content(owner, repo, &path, &r#ref)
+                    .await
+                    .with_context(|| format!("Failed to fetch {path} at {ref}"))?;
+                anyhow::Ok((r#ref, path, content))
+            })
+            .buffered(CONCURRENT_FETCHES)
+            .try_collect()
+            .await?;
+
+        let mut contents: BTreeMap<String, BTreeMap<String, Option<String>>> = BTreeMap::new();
+        for (r#ref, path) in missing {
+            contents.entry(r#ref).or_default().insert(path, None);
+        }
+        // A file fetched at a ref is there, even if another change says it isn't
+        for (r#ref, path, content) in fetched {
+            contents
+                .entry(r#ref)
+                .or_default()
+                .insert(path, Some(content));
+        }
+
+        Ok(Self {
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            host: parsed.host.clone(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            pr,
+            commits,
+            pr_files,
+            commit_files,
+            contents,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+        serde_json::to_writer(BufWriter::new(file), self)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        let bundle: Self = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("{} isn't a revu bundle", path.display()))?;
+        if bundle.version != BUNDLE_VERSION {
+            anyhow::bail!(
+                "{} was exported by a version of revu that can't be read by this one",
+                path.display()
+            );
+        }
+
+        Ok(bundle)
+    }
+
+    pub fn parsed_url(&self) -> ParsedPrUrl {
+        ParsedPrUrl {
+            forge: ForgeKind::Bundle,
+            host: self.host.clone(),
+            owner: self.owner.clone(),
+            repo: self.repo.clone(),
+            number: self.pr.number,
+        }
+    }
+
+    /// Notice that the pull request is shown as it was when exported
+    pub fn snapshot_notice(&self) -> String {
+        format!(
+            "Offline snapshot exported {}",
+            self.exported_at.format("%Y-%m-%d %H:%M UTC")
+        )
+    }
+}
+
+#[async_trait]
+impl Forge for Bundle {
+    async fn get_pull_request(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _number: u64,
+    ) -> Result<PullRequest> {
+        Ok(self.pr.clone())
+    }
+
+    async fn get_pr_commits(&self, _owner: &str, _repo: &str, _number: u64) -> Result<Vec<Commit>> {
+        Ok(self.commits.clone())
+    }
+
+    async fn get_pr_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _number: u64,
+    ) -> Result<Vec<FileChange>> {
+        Ok(self.pr_files.clone())
+    }
+
+    async fn get_file_content(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        path: &str,
+        r#ref: &str,
+    ) -> Result<String> {
+        match self.contents.get(r#ref).and_then(|files| files.get(path)) {
+            Some(content) => Ok(content.clone().unwrap_or_default()),
+            None => anyhow::bail!("File not in bundle: {path}@{ref}"),
+        }
+    }
+
+    async fn get_commit_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        sha: &str,
+    ) -> Result<Vec<FileChange>> {
+        self.commit_files
+            .get(sha)
+            .cloned()
+            .with_context(|| format!("Commit {sha} isn't in the bundle"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::Patch;
+
+    #[tokio::test]
+    async fn test_bundle_round_trip() {
+        let diff = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n";
+        let patch = Patch::parse("fix.diff", diff).unwrap();
+        let bundle = Bundle::export(&patch, &patch.parsed_url()).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pr.revu");
+        bundle.save(&path).unwrap();
+        let bundle = Bundle::open(&path).unwrap();
+        assert_eq!(bundle.parsed_url().forge, ForgeKind::Bundle);
+
+        let pr = bundle.get_pull_request("", "", 0).await.unwrap();
+        assert_eq!(pr.title, "fix.diff");
+        let commits = bundle.get_pr_commits("", "", 0).await.unwrap();
+        let files = bundle
+            .get_commit_files("", "", &commits[0].sha)
+            .await
+            .unwrap();
+        assert_eq!(files[0].filename, "src/lib.rs");
+        assert_eq!(bundle.contents["patch"]["src/lib.rs"].as_deref(), Some(""));
+        assert!(bundle.get_commit_files("", "", "missing").await.is_err());
+
+        // Contents the export didn't write are an error rather than an empty file
+        let error = bundle
+            .get_file_content("", "", "src/lib.rs", "elsewhere")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "File not in bundle: src/lib.rs@elsewhere"
+        );
+
+        std::fs::write(&path, "not a bundle").unwrap();
+        assert!(Bundle::open(&path).is_err());
+    }
+}
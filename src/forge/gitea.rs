@@ -0,0 +1,469 @@
+use super::Forge;
+use crate::{
+    cache::{FileCacheKey, FileContentCache},
+    github::models::*,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use url::Url;
+
+/// Client for pull requests on a Gitea or Forgejo instance
+#[derive(Clone)]
+pub struct GiteaClient {
+    token: Option<String>,
+    /// REST API base, e.g. https://codeberg.org/api/v1
+    api_url: Url,
+    cache: FileContentCache,
+    http: reqwest::Client,
+}
+
+impl GiteaClient {
+    pub fn new(
+        token: Option<String>,
+        api_url: &str,
+        http: reqwest::Client,
+        cache: FileContentCache,
+    ) -> Result<Self> {
+        let api_url = Url::parse(api_url).context("Invalid Gitea API URL")?;
+        if api_url.cannot_be_a_base() {
+            anyhow::bail!("Invalid Gitea API URL: {api_url}");
+        }
+
+        Ok(Self {
+            token,
+            api_url,
+            cache,
+            http,
+        })
+    }
+
+    /// REST API base of a Gitea or Forgejo instance, e.g. `https://codeberg.org/api/v1`
+    pub fn api_url_for_host(host: &str) -> String {
+        format!("https://{host}/api/v1")
+    }
+
+    /// API URL of a repository endpoint
+    fn repo_url<'a>(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: impl IntoIterator<Item = &'a str>,
+    ) -> Url {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("API URL can be a base")
+            .pop_if_empty()
+            .extend(["repos", owner, repo])
+            .extend(path);
+        url
+    }
+
+    /// GET every page of a list endpoint
+    async fn get_all_pages<T: serde::de::DeserializeOwned>(
+        &self,
+        url: Url,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>> {
+        // Gitea caps pages at 50 items by default
+        const PER_PAGE: usize = 50;
+        let mut items = Vec::new();
+
+        for page in 1.. {
+            let batch: Vec<T> = self
+                .request(url.clone())
+                .query(query)
+                .query(&[("limit", PER_PAGE), ("page", page)])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+                .context("Failed to decode response")?;
+
+            let is_last = batch.len() < PER_PAGE;
+            items.extend(batch);
+            if is_last {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn request(&self, url: Url) -> reqwest::RequestBuilder {
+        let mut request = self.http.get(url).header("Accept", "application/json");
+
+        if let Some(ref token) = self.token {
+            request = request.header("Authorization", format!("token {token}"));
+        }
+        request
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaClient {
+    async fn get_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<PullRequest> {
+        let url = self.repo_url(owner, repo, ["pulls", &number.to_string()]);
+        let pr: GiteaPullRequest = self
+            .request(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Failed to decode pull request")?;
+
+        // Pull requests don't carry their number of commits
+        let commits = self.get_pr_commits(owner, repo, number).await?;
+
+        Ok(pr.into_pull_request(commits.len()))
+    }
+
+    async fn get_pr_commits(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<Commit>> {
+        let url = self.repo_url(owner, repo, ["pulls", &number.to_string(), "commits"]);
+        // Skip the per-commit file lists and stats, they're fetched when a commit is shown
+        let commits: Vec<GiteaCommit> = self
+            .get_all_pages(
+                url,
+                &[
+                    ("stat", "false"),
+                    ("verification", "false"),
+                    ("files", "false"),
+                ],
+            )
+            .await?;
+
+        Ok(oldest_first(commits))
+    }
+
+    async fn get_pr_files(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<FileChange>> {
+        let url = self.repo_url(owner, repo, ["pulls", &number.to_string(), "files"]);
+        let files: Vec<GiteaChangedFile> = self.get_all_pages(url, &[]).await?;
+        Ok(files.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        r#ref: &str,
+    ) -> Result<String> {
+        let cache_key = FileCacheKey {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            path: path.to_string(),
+            sha: r#ref.to_string(),
+        };
+
+        if let Some(cached_content) = self.cache.get(&cache_key).await {
+            return Ok(cached_content);
+        }
+
+        let url = self.repo_url(owner, repo, std::iter::once("raw").chain(path.split('/')));
+        let response = self
+            .request(url)
+            .query(&[("ref", r#ref)])
+            .send()
+            .await
+            .context("Failed to fetch file content")?;
+
+        // The file doesn't exist at this ref, e.g. it was added or deleted
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(String::new());
+        }
+
+        let content = response
+            .error_for_status()
+            .context("Failed to fetch file content")?
+            .text()
+            .await
+            .context("Failed to read file content")?;
+
+        self.cache.put(cache_key, content.clone()).await;
+
+        Ok(content)
+    }
+
+    async fn get_commit_files(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<Vec<FileChange>> {
+        let url = self.repo_url(owner, repo, ["git", "commits", sha]);
+        let commit: GiteaCommitFiles = self
+            .request(url)
+            .query(&[("stat", "false"), ("verification", "false")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Failed to decode commit")?;
+
+        Ok(commit.files.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaPullRequest {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    user: User,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    head: Branch,
+    base: Branch,
+    merge_base: Option<String>,
+    // Only reported by recent versions
+    #[serde(default)]
+    additions: u32,
+    #[serde(default)]
+    deletions: u32,
+    #[serde(default)]
+    changed_files: u32,
+}
+
+impl GiteaPullRequest {
+    fn into_pull_request(self, commit_count: usize) -> PullRequest {
+        let mut base = self.base;
+        // Diff against where the branch forked off, like the pull request page does
+        if let Some(merge_base) = self.merge_base.filter(|sha| !sha.is_empty()) {
+            base.sha = merge_base;
+        }
+
+        PullRequest {
+            number: self.number,
+            title: self.title,
+            body: self.body,
+            state: if self.merged {
+                "merged".to_string()
+            } else {
+                self.state
+            },
+            user: self.user,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            head: self.head,
+            base,
+            commits: commit_count as u32,
+            additions: self.additions,
+            deletions: self.deletions,
+            changed_files: self.changed_files,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ParentRef {
+    sha: String,
+}
+
+/// Commits are shaped like GitHub's, with their parents alongside
+#[derive(Debug, Deserialize)]
+struct GiteaCommit {
+    #[serde(flatten)]
+    commit: Commit,
+    #[serde(default)]
+    parents: Vec<ParentRef>,
+}
+
+/// Put commits in the order they were made.
+/// Versions differ in the order they list pull request commits, so it's worked out from the parents.
+fn oldest_first(mut commits: Vec<GiteaCommit>) -> Vec<Commit> {
+    let newest_first = match commits.as_slice() {
+        [first, second, ..] => first.parents.iter().any(|p| p.sha == second.commit.sha),
+        _ => false,
+    };
+    if newest_first {
+        commits.reverse();
+    }
+    commits.into_iter().map(|c| c.commit).collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaChangedFile {
+    filename: String,
+    status: String,
+    #[serde(default)]
+    additions: u32,
+    #[serde(default)]
+    deletions: u32,
+}
+
+impl From<GiteaChangedFile> for FileChange {
+    fn from(file: GiteaChangedFile) -> Self {
+        let status = match file.status.as_str() {
+            "added" => FileStatus::Added,
+            "deleted" | "removed" => FileStatus::Deleted,
+            "renamed" => FileStatus::Renamed,
+            "copied" => FileStatus::Copied,
+            _ => FileStatus::Modified,
+        };
+
+        FileChange {
+            filename: file.filename,
+            status,
+            additions: file.additions,
+            deletions: file.deletions,
+            // Gitea doesn't include patches, the diff is built from the file contents
+            patch: None,
+            raw_content: None,
+            diff_content: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaCommitFiles {
+    #[serde(default)]
+    files: Vec<GiteaChangedFile>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    const OWNER: &str = "tools";
+    const REPO: &str = "deployer";
+
+    async fn mock_json(server: &mut mockito::ServerGuard, path: &str, body: &str) -> mockito::Mock {
+        server
+            .mock("GET", path)
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    fn client(server: &mockito::ServerGuard) -> GiteaClient {
+        GiteaClient::new(
+            Some("secret".to_string()),
+            &format!("{}/api/v1", server.url()),
+            reqwest::Client::new(),
+            FileContentCache::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_api_url_must_be_a_base() {
+        let client = GiteaClient::new(
+            None,
+            "mailto:gitea@example.com",
+            reqwest::Client::new(),
+            FileContentCache::default(),
+        );
+        assert!(client.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pull_request_from_fixtures() {
+        let mut server = mockito::Server::new_async().await;
+        mock_json(
+            &mut server,
+            "/api/v1/repos/tools/deployer/pulls/7",
+            include_str!("../../tests/fixtures/gitea/pull.json"),
+        )
+        .await;
+        mock_json(
+            &mut server,
+            "/api/v1/repos/tools/deployer/pulls/7/commits",
+            include_str!("../../tests/fixtures/gitea/commits.json"),
+        )
+        .await;
+        let client = client(&server);
+
+        let pr = client.get_pull_request(OWNER, REPO, 7).await.unwrap();
+        assert_eq!(pr.title, "Roll back failed deployments");
+        assert_eq!(pr.state, "open");
+        assert_eq!(pr.user.login, "mira");
+        assert_eq!(pr.head.r#ref, "rollback");
+        // The merge base is used rather than the base branch's tip
+        assert_eq!(pr.base.sha, "1111111111111111111111111111111111111111");
+        assert_eq!((pr.commits, pr.changed_files), (2, 2));
+        assert_eq!((pr.additions, pr.deletions), (12, 3));
+
+        // The fixture lists the newest commit first
+        let commits = client.get_pr_commits(OWNER, REPO, 7).await.unwrap();
+        let messages: Vec<&str> = commits.iter().map(|c| c.commit.message.as_str()).collect();
+        assert_eq!(messages, ["Add rollback command", "Retry health checks"]);
+        assert_eq!(commits[0].author.as_ref().unwrap().login, "mira");
+        assert!(commits[1].author.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_files_from_fixtures() {
+        let mut server = mockito::Server::new_async().await;
+        mock_json(
+            &mut server,
+            "/api/v1/repos/tools/deployer/pulls/7/files",
+            include_str!("../../tests/fixtures/gitea/files.json"),
+        )
+        .await;
+        mock_json(
+            &mut server,
+            "/api/v1/repos/tools/deployer/git/commits/3333333333333333333333333333333333333333",
+            include_str!("../../tests/fixtures/gitea/commit.json"),
+        )
+        .await;
+        let raw = server
+            .mock("GET", "/api/v1/repos/tools/deployer/raw/src/deploy.rs")
+            .match_query(Matcher::UrlEncoded("ref".into(), "head".into()))
+            .match_header("authorization", "token secret")
+            .with_body("fn deploy() {}\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v1/repos/tools/deployer/raw/src/missing.rs")
+            .match_query(Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+        let client = client(&server);
+
+        let files = client.get_pr_files(OWNER, REPO, 7).await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].filename, "src/deploy.rs");
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!((files[0].additions, files[0].deletions), (9, 3));
+        assert_eq!(files[1].status, FileStatus::Added);
+
+        let files = client
+            .get_commit_files(OWNER, REPO, "3333333333333333333333333333333333333333")
+            .await
+            .unwrap();
+        let statuses: Vec<_> = files.iter().map(|f| f.status.clone()).collect();
+        assert_eq!(statuses, [FileStatus::Modified, FileStatus::Deleted]);
+
+        let content = client
+            .get_file_content(OWNER, REPO, "src/deploy.rs", "head")
+            .await
+            .unwrap();
+        assert_eq!(content, "fn deploy() {}\n");
+        raw.assert_async().await;
+
+        // Missing files read as empty
+        let content = client
+            .get_file_content(OWNER, REPO, "src/missing.rs", "head")
+            .await
+            .unwrap();
+        assert!(content.is_empty());
+
+        // Other failures aren't mistaken for missing files
+        assert!(client
+            .get_file_content(OWNER, REPO, "src/broken.rs", "head")
+            .await
+            .is_err());
+    }
+}
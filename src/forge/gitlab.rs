@@ -0,0 +1,480 @@
+use super::{Forge, ForgeKind};
+use crate::{
+    cache::{FileCacheKey, FileContentCache},
+    github::models::*,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use url::Url;
+
+/// Client for merge requests on gitlab.com or a self-managed GitLab instance
+#[derive(Clone)]
+pub struct GitLabClient {
+    token: Option<String>,
+    /// REST API base, e.g. https://gitlab.com/api/v4
+    api_url: Url,
+    cache: FileContentCache,
+    http: reqwest::Client,
+}
+
+impl GitLabClient {
+    pub fn new(
+        token: Option<String>,
+        api_url: &str,
+        http: reqwest::Client,
+        cache: FileContentCache,
+    ) -> Result<Self> {
+        let api_url = Url::parse(api_url).context("Invalid GitLab API URL")?;
+        if api_url.cannot_be_a_base() {
+            anyhow::bail!("Invalid GitLab API URL: {api_url}");
+        }
+
+        Ok(Self {
+            token,
+            api_url,
+            cache,
+            http,
+        })
+    }
+
+    /// REST API base of a GitLab instance, e.g. `https://gitlab.com/api/v4`
+    pub fn api_url_for_host(host: &str) -> String {
+        format!("https://{host}/api/v4")
+    }
+
+    /// Parse a merge request URL like `https://gitlab.com/group/subgroup/project/-/merge_requests/42`.
+    /// The owner is the project's full namespace, which may contain slashes.
+    pub fn parse_mr_url(url: &str) -> Result<ParsedPrUrl> {
+        let re = Regex::new(r"^(?:https?://)?([^/\s]+)/(.+)/([^/]+)/-/merge_requests/(\d+)")
+            .context("Failed to create regex")?;
+
+        let caps = re
+            .captures(url)
+            .context("Invalid GitLab merge request URL format")?;
+
+        Ok(ParsedPrUrl {
+            forge: ForgeKind::GitLab,
+            host: Some(caps[1].to_lowercase()),
+            owner: caps[2].to_string(),
+            repo: caps[3].to_string(),
+            number: caps[4].parse()?,
+        })
+    }
+
+    /// API URL of a project endpoint. The project is addressed by its encoded full path.
+    fn project_url(&self, owner: &str, repo: &str, path: &[&str]) -> Url {
+        let mut url = self.api_url.clone();
+        url.path_segments_mut()
+            .expect("API URL can be a base")
+            .pop_if_empty()
+            .push("projects")
+            .push(&format!("{owner}/{repo}"))
+            .extend(path);
+        url
+    }
+
+    /// GET every page of a list endpoint
+    async fn get_all_pages<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<Vec<T>> {
+        const PER_PAGE: usize = 100;
+        let mut items = Vec::new();
+
+        for page in 1.. {
+            let batch: Vec<T> = self
+                .request(url.clone())
+                .query(&[("per_page", PER_PAGE), ("page", page)])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+                .context("Failed to decode response")?;
+
+            let is_last = batch.len() < PER_PAGE;
+            items.extend(batch);
+            if is_last {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn request(&self, url: Url) -> reqwest::RequestBuilder {
+        let mut request = self.http.get(url);
+
+        if let Some(ref token) = self.token {
+            request = request.header("PRIVATE-TOKEN", token);
+        }
+        request
+    }
+}
+
+#[async_trait]
+impl Forge for GitLabClient {
+    async fn get_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<PullRequest> {
+        let url = self.project_url(owner, repo, &["merge_requests", &number.to_string()]);
+        let mr: MergeRequest = self
+            .request(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .context("Failed to decode merge request")?;
+
+        Ok(mr.into())
+    }
+
+    async fn get_pr_commits(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<Commit>> {
+        let url = self.project_url(
+            owner,
+            repo,
+            &["merge_requests", &number.to_string(), "commits"],
+        );
+        let commits: Vec<GitLabCommit> = self.get_all_pages(url).await?;
+
+        // GitLab lists merge request commits newest first
+        Ok(commits.into_iter().rev().map(Into::into).collect())
+    }
+
+    async fn get_pr_files(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<FileChange>> {
+        let url = self.project_url(
+            owner,
+            repo,
+            &["merge_requests", &number.to_string(), "diffs"],
+        );
+        let diffs: Vec<GitLabDiff> = self.get_all_pages(url).await?;
+        Ok(diffs.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        r#ref: &str,
+    ) -> Result<String> {
+        let cache_key = FileCacheKey {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            path: path.to_string(),
+            sha: r#ref.to_string(),
+        };
+
+        if let Some(cached_content) = self.cache.get(&cache_key).await {
+            return Ok(cached_content);
+        }
+
+        let url = self.project_url(owner, repo, &["repository", "files", path, "raw"]);
+        let response = self
+            .request(url)
+            .query(&[("ref", r#ref)])
+            .send()
+            .await
+            .context("Failed to fetch file content")?;
+
+        // The file doesn't exist at this ref, e.g. it was added or deleted
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(String::new());
+        }
+
+        let content = response
+            .error_for_status()
+            .context("Failed to fetch file content")?
+            .text()
+            .await
+            .context("Failed to read file content")?;
+
+        self.cache.put(cache_key, content.clone()).await;
+
+        Ok(content)
+    }
+
+    async fn get_commit_files(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<Vec<FileChange>> {
+        let url = self.project_url(owner, repo, &["repository", "commits", sha, "diff"]);
+        let diffs: Vec<GitLabDiff> = self.get_all_pages(url).await?;
+        Ok(diffs.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabUser {
+    username: String,
+    #[serde(default)]
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DiffRefs {
+    base_sha: String,
+    head_sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+    state: String,
+    author: GitLabUser,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    source_branch: String,
+    target_branch: String,
+    diff_refs: DiffRefs,
+    /// Number of files changed, like "12", or "1000+" past GitLab's limit
+    changes_count: Option<String>,
+}
+
+/// Merge requests only count the files they change; other totals are left at zero to be counted
+/// from the commits and files listed
+impl From<MergeRequest> for PullRequest {
+    fn from(mr: MergeRequest) -> Self {
+        let changed_files = mr
+            .changes_count
+            .as_deref()
+            .and_then(|count| count.trim_end_matches('+').parse().ok())
+            .unwrap_or(0);
+
+        PullRequest {
+            number: mr.iid,
+            title: mr.title,
+            body: mr.description,
+            state: match mr.state.as_str() {
+                "opened" => "open".to_string(),
+                _ => mr.state,
+            },
+            user: User {
+                login: mr.author.username,
+                avatar_url: mr.author.avatar_url.unwrap_or_default(),
+            },
+            created_at: mr.created_at,
+            updated_at: mr.updated_at,
+            head: Branch {
+                label: mr.source_branch.clone(),
+                r#ref: mr.source_branch,
+                sha: mr.diff_refs.head_sha,
+            },
+            base: Branch {
+                label: mr.target_branch.clone(),
+                r#ref: mr.target_branch,
+                sha: mr.diff_refs.base_sha,
+            },
+            commits: 0,
+            additions: 0,
+            deletions: 0,
+            changed_files,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabCommit {
+    id: String,
+    message: String,
+    author_name: String,
+    author_email: String,
+    authored_date: DateTime<Utc>,
+    committer_name: String,
+    committer_email: String,
+    committed_date: DateTime<Utc>,
+}
+
+impl From<GitLabCommit> for Commit {
+    fn from(commit: GitLabCommit) -> Self {
+        Commit {
+            sha: commit.id,
+            commit: CommitDetail {
+                message: commit.message,
+                author: CommitAuthor {
+                    name: commit.author_name,
+                    email: commit.author_email,
+                    date: commit.authored_date,
+                },
+                committer: CommitAuthor {
+                    name: commit.committer_name,
+                    email: commit.committer_email,
+                    date: commit.committed_date,
+                },
+            },
+            // GitLab commits aren't linked to user accounts
+            author: None,
+            committer: None,
+        }
+    }
+}
+
+/// A changed file, as listed for merge requests and commits
+#[derive(Debug, Deserialize)]
+struct GitLabDiff {
+    new_path: String,
+    diff: String,
+    new_file: bool,
+    renamed_file: bool,
+    deleted_file: bool,
+}
+
+impl From<GitLabDiff> for FileChange {
+    fn from(diff: GitLabDiff) -> Self {
+        let status = if diff.new_file {
+            FileStatus::Added
+        } else if diff.deleted_file {
+            FileStatus::Deleted
+        } else if diff.renamed_file {
+            FileStatus::Renamed
+        } else {
+            FileStatus::Modified
+        };
+
+        // The diff starts at the first hunk, without file headers
+        let count = |prefix: char| diff.diff.lines().filter(|l| l.starts_with(prefix)).count();
+        let additions = count('+') as u32;
+        let deletions = count('-') as u32;
+
+        FileChange {
+            filename: diff.new_path,
+            status,
+            additions,
+            deletions,
+            // Large diffs are left out of the response
+            patch: Some(diff.diff).filter(|d| !d.is_empty()),
+            raw_content: None,
+            diff_content: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mr_url() {
+        let parsed = GitLabClient::parse_mr_url(
+            "https://gitlab.com/acme/platform/api/-/merge_requests/42/diffs",
+        )
+        .unwrap();
+        assert_eq!(parsed.forge, ForgeKind::GitLab);
+        assert_eq!(parsed.host.as_deref(), Some("gitlab.com"));
+        assert_eq!(parsed.owner, "acme/platform");
+        assert_eq!(parsed.repo, "api");
+        assert_eq!(parsed.number, 42);
+
+        assert!(GitLabClient::parse_mr_url("https://github.com/owner/repo/pull/1").is_err());
+        assert!(GitLabClient::parse_mr_url("42").is_err());
+    }
+
+    #[test]
+    fn test_project_url_encoding() {
+        let client = GitLabClient::new(
+            None,
+            "https://gitlab.example.com/api/v4/",
+            reqwest::Client::new(),
+            FileContentCache::default(),
+        )
+        .unwrap();
+        let url = client.project_url(
+            "acme/platform",
+            "api",
+            &["repository", "files", "src/main.rs", "raw"],
+        );
+        assert_eq!(
+            url.as_str(),
+            "https://gitlab.example.com/api/v4/projects/acme%2Fplatform%2Fapi/repository/files/src%2Fmain.rs/raw"
+        );
+
+        let client = GitLabClient::new(
+            None,
+            "mailto:gitlab@example.com",
+            reqwest::Client::new(),
+            FileContentCache::default(),
+        );
+        assert!(client.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_missing_files_are_empty() {
+        let mut server = mockito::Server::new_async().await;
+        let file = "/projects/acme%2Fapi/repository/files/src%2Fmain.rs/raw";
+        server
+            .mock("GET", file)
+            .match_query(mockito::Matcher::UrlEncoded("ref".into(), "base".into()))
+            .with_status(404)
+            .create_async()
+            .await;
+        server
+            .mock("GET", file)
+            .match_query(mockito::Matcher::UrlEncoded("ref".into(), "head".into()))
+            .with_status(500)
+            .create_async()
+            .await;
+
+        let client = GitLabClient::new(
+            None,
+            &server.url(),
+            reqwest::Client::new(),
+            FileContentCache::default(),
+        )
+        .unwrap();
+        let content = client
+            .get_file_content("acme", "api", "src/main.rs", "base")
+            .await
+            .unwrap();
+        assert!(content.is_empty());
+        assert!(client
+            .get_file_content("acme", "api", "src/main.rs", "head")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_merge_request_mapping() {
+        let mr: MergeRequest = serde_json::from_value(serde_json::json!({
+            "iid": 42,
+            "title": "Add retries",
+            "description": "Retries failed uploads",
+            "state": "opened",
+            "author": { "username": "jdoe", "avatar_url": null },
+            "created_at": "2024-05-01T10:00:00.000Z",
+            "updated_at": "2024-05-02T10:00:00.000Z",
+            "source_branch": "retries",
+            "target_branch": "main",
+            "diff_refs": { "base_sha": "base", "head_sha": "head", "start_sha": "start" },
+            "changes_count": "1"
+        }))
+        .unwrap();
+
+        let diff: GitLabDiff = serde_json::from_value(serde_json::json!({
+            "old_path": "src/upload.rs",
+            "new_path": "src/upload.rs",
+            "diff": "@@ -1,2 +1,3 @@\n fn upload() {\n-    send();\n+    retry(send);\n+    log();\n",
+            "new_file": false,
+            "renamed_file": false,
+            "deleted_file": false
+        }))
+        .unwrap();
+        let file = FileChange::from(diff);
+        assert_eq!(file.status, FileStatus::Modified);
+        assert_eq!((file.additions, file.deletions), (2, 1));
+
+        let mut pr = PullRequest::from(mr);
+        assert_eq!(pr.state, "open");
+        assert_eq!(pr.user.login, "jdoe");
+        assert_eq!(pr.head.sha, "head");
+        assert_eq!(pr.base.r#ref, "main");
+        assert_eq!((pr.commits, pr.changed_files), (0, 1));
+
+        pr.count_missing_totals(3, &[file]);
+        assert_eq!((pr.commits, pr.changed_files), (3, 1));
+        assert_eq!((pr.additions, pr.deletions), (2, 1));
+    }
+}
@@ -0,0 +1,692 @@
+use super::{pseudo_commit, Forge, ForgeKind};
+use crate::{
+    diff::{compare::text, DiffParser},
+    github::models::*,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+
+/// A range of commits in a local git repository, read by running `git`.
+/// Reviewed like a pull request from `base` into `head`, without any network access.
+#[derive(Clone)]
+pub struct LocalRepo {
+    /// Top level of the working tree
+    dir: PathBuf,
+    base: String,
+    head: String,
+}
+
+impl LocalRepo {
+    /// Open the repository containing `dir` to review `range`, given as `base..head`.
+    /// `head` defaults to `HEAD` when left out, as in `main..`.
+    pub async fn open(dir: &Path, range: &str) -> Result<Self> {
+        let (base, head) = parse_range(range)?;
+        let repo = Self::at_toplevel(dir, base, head).await?;
+
+        for rev in [&repo.base, &repo.head] {
+            repo.rev_parse(rev)
+                .await
+                .with_context(|| format!("Unknown revision '{rev}'"))?;
+        }
+
+        Ok(repo)
+    }
+
+    /// Find the top level of the working tree containing `dir`, which git reports paths relative to
+    async fn at_toplevel(dir: &Path, base: String, head: String) -> Result<Self> {
+        let mut repo = Self {
+            dir: dir.to_path_buf(),
+            base,
+            head,
+        };
+
+        let toplevel = repo
+            .git(&["rev-parse", "--show-toplevel"])
+            .await
+            .context("Not inside a git repository")?;
+        repo.dir = PathBuf::from(toplevel.trim_end());
+        Ok(repo)
+    }
+
+    /// Stands in for a pull request URL, naming the repository after its directory
+    pub fn parsed_url(&self) -> ParsedPrUrl {
+        let name = self
+            .dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "repo".to_string());
+
+        ParsedPrUrl {
+            forge: ForgeKind::Local,
+            host: None,
+            owner: "local".to_string(),
+            repo: name,
+            number: 0,
+        }
+    }
+
+    /// Run git in the repository and return its output
+    async fn git(&self, args: &[&str]) -> Result<String> {
+        let stdout = self.git_bytes(args).await?;
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+
+    /// Run git in the repository and return its output as is
+    async fn git_bytes(&self, args: &[&str]) -> Result<Vec<u8>> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .await
+            .context("Failed to run git")?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Whether `path` is in the tree of `ref`, or in the index when `ref` is empty.
+    /// Fails when `ref` itself can't be read.
+    async fn path_exists(&self, path: &str, r#ref: &str) -> Result<bool> {
+        let listing = if r#ref.is_empty() {
+            self.git(&["ls-files", "--cached", "-z", "--", path]).await
+        } else {
+            self.git(&["ls-tree", "-z", r#ref, "--", path]).await
+        };
+        let listing = listing.with_context(|| format!("Failed to look up {path} at {ref}"))?;
+        Ok(!listing.is_empty())
+    }
+
+    async fn rev_parse(&self, rev: &str) -> Result<String> {
+        let sha = self
+            .git(&[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{rev}^{{commit}}"),
+            ])
+            .await?;
+        Ok(sha.trim().to_string())
+    }
+
+    /// Commit `head` forked off `base` at, which the range is compared against
+    async fn merge_base(&self) -> Result<String> {
+        let sha = self.git(&["merge-base", &self.base, &self.head]).await?;
+        Ok(sha.trim().to_string())
+    }
+
+    /// Files changed in a `git diff` of `revs`, with their patches
+    async fn diff_files(&self, revs: &[&str]) -> Result<Vec<FileChange>> {
+        let diff = |options: &[&'static str]| {
+            let mut args = vec!["diff", "-M", "--no-color", "--no-ext-diff"];
+            args.extend(options);
+            args.extend(revs);
+            args
+        };
+        let name_status = self.git(&diff(&["--name-status", "-z"])).await?;
+        let numstat = self.git(&diff(&["--numstat", "-z"])).await?;
+        let mut patches: HashMap<String, String> =
+            DiffParser::split_unified_diff(&self.git(&diff(&[])).await?)?
+                .into_iter()
+                .filter_map(|file| Some((file.filename, file.patch?)))
+                .collect();
+
+        let mut files = parse_diff_files(&name_status, &numstat);
+        for file in &mut files {
+            file.patch = patches.remove(&file.filename);
+        }
+        Ok(files)
+    }
+
+    /// Hash of the empty tree, to compare a root commit or an unborn branch against
+    async fn empty_tree(&self) -> Result<String> {
+        let sha = self
+            .git(&["hash-object", "-t", "tree", "/dev/null"])
+            .await?;
+        Ok(sha.trim().to_string())
+    }
+}
+
+#[async_trait]
+impl Forge for LocalRepo {
+    async fn get_pull_request(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        number: u64,
+    ) -> Result<PullRequest> {
+        let base_sha = self.merge_base().await?;
+        let head_sha = self.rev_parse(&self.head).await?;
+        let commits = self.get_pr_commits("", "", number).await?;
+        let files = self.diff_files(&[&base_sha, &head_sha]).await?;
+
+        let author = commits.first().map(|c| c.commit.author.clone());
+        let updated_at = commits
+            .last()
+            .map(|c| c.commit.committer.date)
+            .unwrap_or_else(Utc::now);
+
+        Ok(PullRequest {
+            number,
+            title: format!("{}..{}", self.base, self.head),
+            body: None,
+            state: "local".to_string(),
+            user: User {
+                login: author.as_ref().map(|a| a.name.clone()).unwrap_or_default(),
+                avatar_url: String::new(),
+            },
+            created_at: author.map(|a| a.date).unwrap_or(updated_at),
+            updated_at,
+            head: Branch {
+                label: self.head.clone(),
+                r#ref: self.head.clone(),
+                sha: head_sha,
+            },
+            base: Branch {
+                label: self.base.clone(),
+                r#ref: self.base.clone(),
+                sha: base_sha,
+            },
+            commits: commits.len() as u32,
+            additions: files.iter().map(|f| f.additions).sum(),
+            deletions: files.iter().map(|f| f.deletions).sum(),
+            changed_files: files.len() as u32,
+        })
+    }
+
+    async fn get_pr_commits(&self, _owner: &str, _repo: &str, _number: u64) -> Result<Vec<Commit>> {
+        let range = format!("{}..{}", self.base, self.head);
+        let log = self
+            .git(&[
+                "log",
+                "--reverse",
+                &format!("--format={LOG_FORMAT}"),
+                &range,
+            ])
+            .await?;
+        parse_log(&log)
+    }
+
+    async fn get_pr_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _number: u64,
+    ) -> Result<Vec<FileChange>> {
+        let base = self.merge_base().await?;
+        self.diff_files(&[&base, &self.head]).await
+    }
+
+    async fn get_file_content(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        path: &str,
+        r#ref: &str,
+    ) -> Result<String> {
+        // File might not exist in this ref (e.g., deleted file)
+        if !self.path_exists(path, r#ref).await? {
+            return Ok(String::new());
+        }
+        let content = self
+            .git_bytes(&["show", &format!("{ref}:{path}")])
+            .await
+            .with_context(|| format!("Failed to read {path} at {ref}"))?;
+        Ok(text(Some(content)))
+    }
+
+    async fn get_commit_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        sha: &str,
+    ) -> Result<Vec<FileChange>> {
+        // Compare with the first parent, or with an empty tree for the root commit
+        let parent = match self.rev_parse(&format!("{sha}^")).await {
+            Ok(parent) => parent,
+            Err(_) => self.empty_tree().await?,
+        };
+        self.diff_files(&[&parent, sha]).await
+    }
+}
+
+/// Pseudo-commit holding the changes added to the index
+const STAGED: &str = "staged";
+/// Pseudo-commit holding the changes in the working tree that aren't staged
+const UNSTAGED: &str = "unstaged";
+
+/// Uncommitted changes of a local repository, reviewed as two pseudo-commits on top of HEAD:
+/// the staged changes, then the unstaged ones
+#[derive(Clone)]
+pub struct WorkingTree {
+    repo: LocalRepo,
+}
+
+impl WorkingTree {
+    /// Open the repository containing `dir`
+    pub async fn open(dir: &Path) -> Result<Self> {
+        let repo = LocalRepo::at_toplevel(dir, "HEAD".to_string(), "HEAD".to_string()).await?;
+        Ok(Self { repo })
+    }
+
+    pub fn parsed_url(&self) -> ParsedPrUrl {
+        self.repo.parsed_url()
+    }
+
+    /// The commit the changes are on top of
+    async fn head_sha(&self) -> Result<String> {
+        match self.repo.rev_parse("HEAD").await {
+            Ok(sha) => Ok(sha),
+            Err(_) => self.repo.empty_tree().await,
+        }
+    }
+
+    /// Contents of a file on disk. Binary files read as empty, like in the diff.
+    async fn read_file(&self, path: &str) -> Result<String> {
+        match tokio::fs::read(self.repo.dir.join(path)).await {
+            Ok(bytes) => Ok(text(Some(bytes))),
+            // File might not exist on disk (e.g., deleted file)
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {path}")),
+        }
+    }
+
+    /// New files git doesn't track yet, shown as unstaged additions
+    async fn untracked_files(&self) -> Result<Vec<FileChange>> {
+        let output = self
+            .repo
+            .git(&["ls-files", "--others", "--exclude-standard", "-z"])
+            .await?;
+
+        let mut files = Vec::new();
+        for path in output.split('\0').filter(|p| !p.is_empty()) {
+            let content = self.read_file(path).await?;
+            files.push(FileChange {
+                filename: path.to_string(),
+                status: FileStatus::Added,
+                additions: content.lines().count() as u32,
+                deletions: 0,
+                patch: None,
+                raw_content: None,
+                diff_content: None,
+            });
+        }
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl Forge for WorkingTree {
+    async fn get_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<PullRequest> {
+        let base_sha = self.head_sha().await?;
+        let commits = self.get_pr_commits(owner, repo, number).await?;
+        let files = self.get_pr_files(owner, repo, number).await?;
+        let now = Utc::now();
+
+        Ok(PullRequest {
+            number,
+            title: "Uncommitted changes".to_string(),
+            body: None,
+            state: "local".to_string(),
+            user: User {
+                login: String::new(),
+                avatar_url: String::new(),
+            },
+            created_at: now,
+            updated_at: now,
+            head: Branch {
+                label: "working tree".to_string(),
+                r#ref: UNSTAGED.to_string(),
+                sha: UNSTAGED.to_string(),
+            },
+            base: Branch {
+                label: "HEAD".to_string(),
+                r#ref: "HEAD".to_string(),
+                sha: base_sha,
+            },
+            commits: commits.len() as u32,
+            additions: files.iter().map(|f| f.additions).sum(),
+            deletions: files.iter().map(|f| f.deletions).sum(),
+            changed_files: files.len() as u32,
+        })
+    }
+
+    /// The staged and unstaged pseudo-commits, leaving out the ones without changes
+    async fn get_pr_commits(&self, owner: &str, repo: &str, _number: u64) -> Result<Vec<Commit>> {
+        let mut commits = Vec::new();
+        for (sha, message) in [(STAGED, "Staged changes"), (UNSTAGED, "Unstaged changes")] {
+            if !self.get_commit_files(owner, repo, sha).await?.is_empty() {
+                commits.push(pseudo_commit(sha, message));
+            }
+        }
+        Ok(commits)
+    }
+
+    async fn get_pr_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _number: u64,
+    ) -> Result<Vec<FileChange>> {
+        let head = self.head_sha().await?;
+        let mut files = self.repo.diff_files(&[&head]).await?;
+        files.extend(self.untracked_files().await?);
+        Ok(files)
+    }
+
+    async fn get_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        r#ref: &str,
+    ) -> Result<String> {
+        match r#ref {
+            STAGED => self.repo.get_file_content(owner, repo, path, "").await,
+            UNSTAGED => self.read_file(path).await,
+            _ => self.repo.get_file_content(owner, repo, path, r#ref).await,
+        }
+    }
+
+    async fn get_commit_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        sha: &str,
+    ) -> Result<Vec<FileChange>> {
+        match sha {
+            STAGED => self.repo.diff_files(&["--cached"]).await,
+            UNSTAGED => {
+                let mut files = self.repo.diff_files(&[]).await?;
+                files.extend(self.untracked_files().await?);
+                Ok(files)
+            }
+            _ => self.repo.get_commit_files("", "", sha).await,
+        }
+    }
+}
+
+/// Split `base..head` into its revisions
+fn parse_range(range: &str) -> Result<(String, String)> {
+    let (base, head) = range
+        .split_once("..")
+        .filter(|(base, head)| !base.is_empty() && !head.starts_with('.'))
+        .context("Expected a range like main..feature")?;
+    let head = if head.is_empty() { "HEAD" } else { head };
+    Ok((base.to_string(), head.to_string()))
+}
+
+/// Fields of a commit, separated by NUL and ended by a record separator
+const LOG_FORMAT: &str = "%H%x00%an%x00%ae%x00%aI%x00%cn%x00%ce%x00%cI%x00%B%x1e";
+
+fn parse_log(log: &str) -> Result<Vec<Commit>> {
+    log.split('\x1e')
+        .map(str::trim_start)
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let fields: Vec<&str> = record.splitn(8, '\0').collect();
+            let [sha, author, author_email, authored, committer, committer_email, committed, message] =
+                fields[..]
+            else {
+                anyhow::bail!("Unexpected git log output");
+            };
+            let date = |d: &str| -> Result<DateTime<Utc>> {
+                Ok(DateTime::parse_from_rfc3339(d)
+                    .context("Invalid commit date")?
+                    .with_timezone(&Utc))
+            };
+
+            Ok(Commit {
+                sha: sha.to_string(),
+                commit: CommitDetail {
+                    message: message.trim_end().to_string(),
+                    author: CommitAuthor {
+                        name: author.to_string(),
+                        email: author_email.to_string(),
+                        date: date(authored)?,
+                    },
+                    committer: CommitAuthor {
+                        name: committer.to_string(),
+                        email: committer_email.to_string(),
+                        date: date(committed)?,
+                    },
+                },
+                author: None,
+                committer: None,
+            })
+        })
+        .collect()
+}
+
+/// Combine `git diff --name-status -z` and `git diff --numstat -z` output
+fn parse_diff_files(name_status: &str, numstat: &str) -> Vec<FileChange> {
+    // numstat lists `added\tdeleted\tpath`, or `added\tdeleted\t` followed by both paths of a rename
+    let mut counts = HashMap::new();
+    let mut tokens = numstat.split('\0');
+    while let Some(entry) = tokens.next() {
+        let mut parts = entry.splitn(3, '\t');
+        let (Some(added), Some(deleted), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let path = if path.is_empty() {
+            tokens.next();
+            tokens.next().unwrap_or_default()
+        } else {
+            path
+        };
+        // Binary files are counted as `-`
+        counts.insert(
+            path.to_string(),
+            (added.parse().unwrap_or(0), deleted.parse().unwrap_or(0)),
+        );
+    }
+
+    let mut files = Vec::new();
+    let mut tokens = name_status.split('\0').filter(|t| !t.is_empty());
+    while let Some(code) = tokens.next() {
+        let status = match code.chars().next() {
+            Some('A') => FileStatus::Added,
+            Some('D') => FileStatus::Deleted,
+            Some('R') => FileStatus::Renamed,
+            Some('C') => FileStatus::Copied,
+            _ => FileStatus::Modified,
+        };
+        // Renames and copies list the old path first
+        if matches!(status, FileStatus::Renamed | FileStatus::Copied) {
+            tokens.next();
+        }
+        let Some(path) = tokens.next() else {
+            break;
+        };
+
+        let (additions, deletions) = counts.get(path).copied().unwrap_or((0, 0));
+        files.push(FileChange {
+            filename: path.to_string(),
+            status,
+            additions,
+            deletions,
+            patch: None,
+            raw_content: None,
+            diff_content: None,
+        });
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            parse_range("main..feature").unwrap(),
+            ("main".to_string(), "feature".to_string())
+        );
+        assert_eq!(parse_range("v1.0..").unwrap().1, "HEAD");
+        assert!(parse_range("main").is_err());
+        assert!(parse_range("main...feature").is_err());
+    }
+
+    #[test]
+    fn test_parse_diff_files() {
+        let name_status = "M\0src/lib.rs\0R087\0old.rs\0new.rs\0A\0logo.png\0";
+        let numstat = "3\t1\tsrc/lib.rs\x002\t2\t\0old.rs\0new.rs\0-\t-\tlogo.png\0";
+
+        let files = parse_diff_files(name_status, numstat);
+        let summary: Vec<_> = files
+            .iter()
+            .map(|f| {
+                (
+                    f.filename.as_str(),
+                    f.status.clone(),
+                    f.additions,
+                    f.deletions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("src/lib.rs", FileStatus::Modified, 3, 1),
+                ("new.rs", FileStatus::Renamed, 2, 2),
+                ("logo.png", FileStatus::Added, 0, 0),
+            ]
+        );
+    }
+
+    async fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .await
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[tokio::test]
+    async fn test_review_local_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-q", "-b", "main"]).await;
+        std::fs::write(path.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(path, &["add", "."]).await;
+        git(path, &["commit", "-q", "-m", "Initial commit"]).await;
+        git(path, &["checkout", "-q", "-b", "feature"]).await;
+        std::fs::write(path.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        git(path, &["commit", "-q", "-am", "Add b"]).await;
+        std::fs::write(path.join("new.rs"), "fn c() {}\n").unwrap();
+        git(path, &["add", "."]).await;
+        git(path, &["commit", "-q", "-m", "Add c\n\nWith a body"]).await;
+
+        let repo = LocalRepo::open(path, "main..feature").await.unwrap();
+        let pr = repo.get_pull_request("", "", 0).await.unwrap();
+        assert_eq!(pr.title, "main..feature");
+        assert_eq!((pr.commits, pr.changed_files, pr.additions), (2, 2, 2));
+
+        let commits = repo.get_pr_commits("", "", 0).await.unwrap();
+        let messages: Vec<&str> = commits.iter().map(|c| c.commit.message.as_str()).collect();
+        assert_eq!(messages, ["Add b", "Add c\n\nWith a body"]);
+
+        let files = repo
+            .get_commit_files("", "", &commits[1].sha)
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].status, FileStatus::Added);
+
+        let content = repo
+            .get_file_content("", "", "lib.rs", &commits[0].sha)
+            .await
+            .unwrap();
+        assert_eq!(content, "fn a() {}\nfn b() {}\n");
+        assert!(repo
+            .get_file_content("", "", "new.rs", &pr.base.sha)
+            .await
+            .unwrap()
+            .is_empty());
+        // Only a missing path reads as empty, not a ref that doesn't exist
+        assert!(repo
+            .get_file_content("", "", "lib.rs", "nope")
+            .await
+            .is_err());
+
+        assert!(LocalRepo::open(path, "main..nope").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_review_working_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path();
+        git(path, &["init", "-q", "-b", "main"]).await;
+        std::fs::write(path.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(path, &["add", "."]).await;
+        git(path, &["commit", "-q", "-m", "Initial commit"]).await;
+
+        // Stage one change, then make another on top of it and add an untracked file
+        std::fs::write(path.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        git(path, &["add", "lib.rs"]).await;
+        std::fs::write(path.join("lib.rs"), "fn a() {}\nfn b() {}\nfn c() {}\n").unwrap();
+        std::fs::write(path.join("notes.md"), "todo\n").unwrap();
+
+        let tree = WorkingTree::open(path).await.unwrap();
+        let commits = tree.get_pr_commits("", "", 0).await.unwrap();
+        let shas: Vec<&str> = commits.iter().map(|c| c.sha.as_str()).collect();
+        assert_eq!(shas, [STAGED, UNSTAGED]);
+        assert_eq!(commits[0].short_sha(), "staged");
+
+        let staged = tree.get_commit_files("", "", STAGED).await.unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(
+            staged[0].patch.as_deref(),
+            Some("@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n")
+        );
+
+        let unstaged = tree.get_commit_files("", "", UNSTAGED).await.unwrap();
+        let names: Vec<&str> = unstaged.iter().map(|f| f.filename.as_str()).collect();
+        assert_eq!(names, ["lib.rs", "notes.md"]);
+        assert_eq!(unstaged[1].status, FileStatus::Added);
+
+        // Old content comes from HEAD, then the index, new content from disk
+        let pr = tree.get_pull_request("", "", 0).await.unwrap();
+        assert_eq!((pr.commits, pr.changed_files, pr.additions), (2, 2, 3));
+        let read = |r: String| {
+            let tree = tree.clone();
+            async move { tree.get_file_content("", "", "lib.rs", &r).await.unwrap() }
+        };
+        assert_eq!(read(pr.base.sha.clone()).await.lines().count(), 1);
+        assert_eq!(read(STAGED.to_string()).await.lines().count(), 2);
+        assert_eq!(read(pr.head.sha.clone()).await.lines().count(), 3);
+
+        // Deleted files read as empty, binary ones too, but a directory can't be read
+        std::fs::remove_file(path.join("lib.rs")).unwrap();
+        assert_eq!(read(pr.head.sha.clone()).await, "");
+        std::fs::write(path.join("logo.png"), b"\x89PNG\0").unwrap();
+        let content = tree.get_file_content("", "", "logo.png", UNSTAGED).await;
+        assert_eq!(content.unwrap(), "");
+        std::fs::create_dir(path.join("docs")).unwrap();
+        assert!(tree
+            .get_file_content("", "", "docs", UNSTAGED)
+            .await
+            .is_err());
+    }
+}
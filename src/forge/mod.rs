@@ -0,0 +1,82 @@
+pub mod bundle;
+pub mod gitea;
+pub mod gitlab;
+pub mod local;
+pub mod patch;
+
+use crate::github::{models::*, GitHubClient};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+
+pub use bundle::Bundle;
+pub use gitea::GiteaClient;
+pub use gitlab::GitLabClient;
+pub use local::{LocalRepo, WorkingTree};
+pub use patch::Patch;
+
+/// Where pull request data comes from.
+/// Covers what the diff review needs; comments, reviews and CI are GitHub only.
+#[async_trait]
+pub trait Forge: Send + Sync {
+    async fn get_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<PullRequest>;
+
+    /// Commits of the pull request, oldest first
+    async fn get_pr_commits(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<Commit>>;
+
+    /// Files changed by the pull request as a whole
+    async fn get_pr_files(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<FileChange>>;
+
+    /// Content of a file at `ref`, empty if the file doesn't exist there
+    async fn get_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        r#ref: &str,
+    ) -> Result<String>;
+
+    /// Files changed by a single commit
+    async fn get_commit_files(&self, owner: &str, repo: &str, sha: &str)
+        -> Result<Vec<FileChange>>;
+}
+
+/// The kind of forge a pull request URL points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    /// Gitea or Forgejo
+    Gitea,
+    /// A range of a local git repository
+    Local,
+    /// A unified diff read from a file or stdin
+    Patch,
+    /// A pull request exported for offline review
+    Bundle,
+}
+
+/// Parse a pull request or merge request URL of any supported forge
+pub fn parse_review_url(url: &str) -> Result<ParsedPrUrl> {
+    GitLabClient::parse_mr_url(url).or_else(|_| GitHubClient::parse_pr_url(url))
+}
+
+/// A commit that stands in for changes that aren't committed, like the working tree or a patch
+fn pseudo_commit(sha: &str, message: &str) -> Commit {
+    let now = Utc::now();
+    let author = CommitAuthor {
+        name: String::new(),
+        email: String::new(),
+        date: now,
+    };
+    Commit {
+        sha: sha.to_string(),
+        commit: CommitDetail {
+            message: message.to_string(),
+            author: author.clone(),
+            committer: author,
+        },
+        author: None,
+        committer: None,
+    }
+}
@@ -0,0 +1,164 @@
+use super::{pseudo_commit, Forge, ForgeKind};
+use crate::{
+    diff::{compare::compare_paths, DiffParser},
+    github::models::*,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use regex::Regex;
+use std::{io::Read, path::Path};
+
+/// Sha of the single pseudo-commit holding the whole patch
+const PATCH: &str = "patch";
+
+/// Changes that come with their diffs, shown as a single pseudo-commit: a unified diff read
+/// from a file or stdin, or two local files or directories compared with each other
+pub struct Patch {
+    /// Where the changes were read from
+    name: String,
+    files: Vec<FileChange>,
+}
+
+impl Patch {
+    /// Read a patch from a file, or from stdin if the path is `-`
+    pub fn read(path: &Path) -> Result<Self> {
+        if path == Path::new("-") {
+            let mut diff = String::new();
+            std::io::stdin()
+                .read_to_string(&mut diff)
+                .context("Failed to read the patch from stdin")?;
+            return Self::parse("stdin", &diff);
+        }
+
+        let diff = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&path.display().to_string(), &diff)
+    }
+
+    /// Compare two local files or directories
+    pub fn compare(old: &Path, new: &Path) -> Result<Self> {
+        let name = format!("{} vs {}", old.display(), new.display());
+        let files = compare_paths(old, new)?;
+        if files.is_empty() {
+            anyhow::bail!("{} and {} are the same", old.display(), new.display());
+        }
+
+        Ok(Self { name, files })
+    }
+
+    /// Parse a unified diff. Only the hunks are known, so files are shown as the patch has them.
+    pub fn parse(name: &str, diff: &str) -> Result<Self> {
+        // git colors what it pipes into a pager
+        let color_re = Regex::new(r"\x1b\[[0-9;]*m")?;
+        let files = DiffParser::split_unified_diff(&color_re.replace_all(diff, ""))?;
+        if files.is_empty() {
+            anyhow::bail!("No changes found in {name}");
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            files,
+        })
+    }
+
+    pub fn parsed_url(&self) -> ParsedPrUrl {
+        ParsedPrUrl {
+            forge: ForgeKind::Patch,
+            host: None,
+            owner: "patch".to_string(),
+            repo: self.name.clone(),
+            number: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl Forge for Patch {
+    async fn get_pull_request(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        number: u64,
+    ) -> Result<PullRequest> {
+        let now = Utc::now();
+        let branch = |label: &str| Branch {
+            label: label.to_string(),
+            r#ref: PATCH.to_string(),
+            sha: PATCH.to_string(),
+        };
+
+        Ok(PullRequest {
+            number,
+            title: self.name.clone(),
+            body: None,
+            state: "patch".to_string(),
+            user: User {
+                login: String::new(),
+                avatar_url: String::new(),
+            },
+            created_at: now,
+            updated_at: now,
+            head: branch("new"),
+            base: branch("old"),
+            commits: 1,
+            additions: self.files.iter().map(|f| f.additions).sum(),
+            deletions: self.files.iter().map(|f| f.deletions).sum(),
+            changed_files: self.files.len() as u32,
+        })
+    }
+
+    async fn get_pr_commits(&self, _owner: &str, _repo: &str, _number: u64) -> Result<Vec<Commit>> {
+        Ok(vec![pseudo_commit(PATCH, &self.name)])
+    }
+
+    async fn get_pr_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _number: u64,
+    ) -> Result<Vec<FileChange>> {
+        Ok(self.files.clone())
+    }
+
+    /// Files come with their diffs already
+    async fn get_file_content(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _path: &str,
+        _ref: &str,
+    ) -> Result<String> {
+        Ok(String::new())
+    }
+
+    async fn get_commit_files(
+        &self,
+        _owner: &str,
+        _repo: &str,
+        _sha: &str,
+    ) -> Result<Vec<FileChange>> {
+        Ok(self.files.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_patch_forge() {
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\x1b[36m@@ -1 +1,2 @@\x1b[m\n fn a() {}\n\x1b[32m+fn b() {}\x1b[m\n";
+        let patch = Patch::parse("fix.diff", diff).unwrap();
+        assert_eq!(patch.parsed_url().forge, ForgeKind::Patch);
+
+        let pr = patch.get_pull_request("", "", 0).await.unwrap();
+        assert_eq!(pr.title, "fix.diff");
+        assert_eq!((pr.commits, pr.changed_files, pr.additions), (1, 1, 1));
+
+        let files = patch.get_commit_files("", "", PATCH).await.unwrap();
+        assert!(files[0].diff_content.is_some());
+
+        assert!(Patch::parse("empty.diff", "").is_err());
+    }
+}
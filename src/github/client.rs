@@ -1,33 +1,102 @@
-use super::models::*;
-use crate::cache::{FileCacheKey, FileContentCache};
+use super::{
+    error::GitHubError,
+    host::GitHubHost,
+    models::*,
+    rate_limit::{RateLimit, RateLimiter},
+};
+use crate::{
+    cache::{fnv1a, CachedResponse, FileCacheKey, FileContentCache, ResponseCache},
+    diff::DiffParser,
+    forge::{Forge, ForgeKind},
+    settings::DiffStrategy,
+};
 use anyhow::{Context, Result};
-use octocrab::Octocrab;
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use lru::LruCache;
 use regex::Regex;
-use reqwest;
+use reqwest::{
+    header::{ACCEPT, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LINK},
+    Method, StatusCode,
+};
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hasher, RandomState},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Retries of a request that failed for a reason that passes
+const MAX_RETRIES: usize = 3;
+
+/// Delay before the first retry, doubled for every retry after it
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+const JSON_MEDIA_TYPE: &str = "application/vnd.github.v3+json";
+/// Commits, comparisons and pull requests rendered as a unified diff
+const DIFF_MEDIA_TYPE: &str = "application/vnd.github.diff";
+/// File contents as they are, rather than base64 in JSON
+const RAW_MEDIA_TYPE: &str = "application/vnd.github.raw+json";
+
+/// Blob SHAs remembered, enough for the files of a few large pull requests
+const BLOB_SHAS: NonZeroUsize = NonZeroUsize::new(10_000).unwrap();
 
 #[derive(Clone)]
 pub struct GitHubClient {
-    client: Option<Octocrab>,
     token: Option<String>,
+    host: GitHubHost,
     cache: FileContentCache,
+    /// Responses of GET requests, revalidated with their ETags
+    responses: ResponseCache,
+    rate_limiter: RateLimiter,
+    http: reqwest::Client,
+    diff_strategy: DiffStrategy,
+    /// Blob SHAs of files at a ref, as listed with the files a commit or pull request changed
+    blob_shas: Arc<Mutex<LruCache<FileCacheKey, String>>>,
+    /// Listings skipped because the files were read from a diff, to be fetched for their blob
+    /// SHAs only if a file's content can't be fetched any other way
+    unlisted: Arc<Mutex<Vec<FileListing>>>,
 }
 
 impl GitHubClient {
-    pub async fn new(token: Option<String>) -> Result<Self> {
-        let client = if let Some(ref t) = token {
-            let builder = Octocrab::builder().personal_token(t.clone());
-            Some(builder.build().context("Failed to build Octocrab client")?)
-        } else {
-            None
-        };
+    pub async fn new(
+        token: Option<String>,
+        host: GitHubHost,
+        http: reqwest::Client,
+        cache: FileContentCache,
+        responses: ResponseCache,
+    ) -> Result<Self> {
+        url::Url::parse(&host.api_url).context("Invalid GitHub API URL")?;
 
         Ok(Self {
-            client,
             token,
-            cache: FileContentCache::new(100),
+            host,
+            cache,
+            responses,
+            rate_limiter: RateLimiter::default(),
+            http,
+            diff_strategy: DiffStrategy::default(),
+            blob_shas: Arc::new(Mutex::new(LruCache::new(BLOB_SHAS))),
+            unlisted: Arc::default(),
         })
     }
 
+    /// Load files and diffs with `strategy` instead of the default
+    pub fn with_diff_strategy(mut self, strategy: DiffStrategy) -> Self {
+        self.diff_strategy = strategy;
+        self
+    }
+
+    pub fn diff_strategy(&self) -> DiffStrategy {
+        self.diff_strategy
+    }
+
+    /// API quota left, as reported by the latest response
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limiter.quota()
+    }
+
     pub fn parse_pr_url(url: &str) -> Result<ParsedPrUrl> {
         // Handle direct PR number
         if let Ok(number) = url.parse::<u64>() {
@@ -35,36 +104,801 @@ impl GitHubClient {
             let owner = std::env::var("GITHUB_OWNER").unwrap_or_else(|_| "owner".to_string());
             let repo = std::env::var("GITHUB_REPO").unwrap_or_else(|_| "repo".to_string());
             return Ok(ParsedPrUrl {
+                forge: ForgeKind::GitHub,
+                host: None,
                 owner,
                 repo,
                 number,
             });
         }
 
-        // Parse GitHub PR URL
-        let re = Regex::new(r"github\.com/([^/]+)/([^/]+)/pull/(\d+)")
+        // Parse GitHub PR URL, on github.com or an Enterprise Server.
+        // Gitea and Forgejo pull requests live under `/pulls/` instead.
+        let re = Regex::new(r"^(?:https?://)?(?:www\.)?([^/\s]+)/([^/]+)/([^/]+)/(pulls?)/(\d+)")
             .context("Failed to create regex")?;
 
         let caps = re.captures(url).context("Invalid GitHub PR URL format")?;
 
         Ok(ParsedPrUrl {
-            owner: caps[1].to_string(),
-            repo: caps[2].to_string(),
-            number: caps[3].parse()?,
+            forge: if &caps[4] == "pulls" {
+                ForgeKind::Gitea
+            } else {
+                ForgeKind::GitHub
+            },
+            host: Some(caps[1].to_lowercase()),
+            owner: caps[2].to_string(),
+            repo: caps[3].to_string(),
+            number: caps[5].parse()?,
         })
     }
 
-    pub async fn get_pull_request(
+    /// Fetch all review comments on the pull request's diff
+    pub async fn get_review_comments(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<Vec<ReviewComment>> {
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("pulls/{number}/comments"));
+        self.get_all_pages(&url, Retention::Session)
+            .await
+            .context("Failed to fetch review comments")
+    }
+
+    /// Fetch the check runs and commit statuses reported on a commit
+    pub async fn get_commit_checks(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<CommitChecks> {
+        const PER_PAGE: usize = 100;
+        let base = self.host.repo_url(owner, repo, &format!("commits/{sha}"));
+
+        let mut check_runs = Vec::new();
+        for page in 1.. {
+            let list: CheckRunList = self
+                .get_json(
+                    &format!("{base}/check-runs"),
+                    page,
+                    PER_PAGE,
+                    Retention::Session,
+                )
+                .await
+                .context("Failed to fetch check runs")?;
+            let is_last = list.check_runs.len() < PER_PAGE
+                || check_runs.len() + list.check_runs.len() >= list.total_count;
+            check_runs.extend(list.check_runs);
+            if is_last {
+                break;
+            }
+        }
+
+        // The combined status already holds only the latest status of each context
+        let combined: CombinedStatus = self
+            .get_json(&format!("{base}/status"), 1, PER_PAGE, Retention::Session)
+            .await
+            .context("Failed to fetch commit statuses")?;
+
+        Ok(CommitChecks {
+            check_runs,
+            statuses: combined.statuses,
+        })
+    }
+
+    /// Fetch the annotations of every check run that reported some
+    pub async fn get_check_annotations(
+        &self,
+        owner: &str,
+        repo: &str,
+        checks: &CommitChecks,
+    ) -> Result<Vec<CheckAnnotation>> {
+        let requests = checks
+            .check_runs
+            .iter()
+            .filter(|run| run.output.annotations_count > 0)
+            .map(|run| async move {
+                let url =
+                    self.host
+                        .repo_url(owner, repo, &format!("check-runs/{}/annotations", run.id));
+                let mut annotations: Vec<CheckAnnotation> = self
+                    .get_all_pages(&url, Retention::Session)
+                    .await
+                    .with_context(|| format!("Failed to fetch annotations of {}", run.name))?;
+                for annotation in &mut annotations {
+                    annotation.check_name = run.name.clone();
+                }
+                Ok::<_, anyhow::Error>(annotations)
+            });
+
+        let batches = futures::future::try_join_all(requests).await?;
+        Ok(batches.into_iter().flatten().collect())
+    }
+
+    /// Fetch checks for several commits concurrently, keyed by commit SHA.
+    /// Commits whose checks fail to load are left out, as if they had none.
+    pub async fn get_checks_for_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        shas: &[String],
+    ) -> HashMap<String, CommitChecks> {
+        let requests: Vec<_> = shas
+            .iter()
+            .map(|sha| async move {
+                let checks = self.get_commit_checks(owner, repo, sha).await.ok()?;
+                Some((sha.clone(), checks))
+            })
+            .collect();
+
+        stream::iter(requests)
+            .buffer_unordered(8)
+            .filter_map(|checks| async move { checks })
+            .collect()
+            .await
+    }
+
+    /// Fetch the pull request conversation: comments, reviews, commits and other events
+    pub async fn get_timeline(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<Vec<TimelineItem>> {
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("issues/{number}/timeline"));
+        let events: Vec<serde_json::Value> = self
+            .get_all_pages(&url, Retention::Session)
+            .await
+            .context("Failed to fetch the pull request timeline")?;
+        Ok(TimelineItem::from_events(&events))
+    }
+
+    /// Post a top-level comment on the pull request conversation
+    pub async fn create_issue_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        body: &str,
+    ) -> Result<()> {
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("issues/{number}/comments"));
+        self.post_json(&url, &serde_json::json!({ "body": body }))
+            .await
+            .context("Failed to post comment")?;
+        Ok(())
+    }
+
+    /// Post a review comment on a single line of the pull request diff
+    pub async fn create_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        comment: &NewReviewComment,
+    ) -> Result<()> {
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("pulls/{number}/comments"));
+        self.post_json(&url, comment)
+            .await
+            .context("Failed to post review comment")?;
+        Ok(())
+    }
+
+    /// Create a pending review holding the given line comments, returning its id
+    pub async fn create_pending_review(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        commit_id: &str,
+        comments: &[DraftReviewComment],
+    ) -> Result<u64> {
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("pulls/{number}/reviews"));
+
+        // Omitting the event leaves the review in the PENDING state
+        let payload = serde_json::json!({
+            "commit_id": commit_id,
+            "comments": comments,
+        });
+
+        let review = self
+            .post_json(&url, &payload)
+            .await
+            .context("Failed to create pending review")?;
+
+        review["id"]
+            .as_u64()
+            .context("GitHub returned a review without an id")
+    }
+
+    /// Delete a pending review along with its comments. A review that's already gone is fine.
+    pub async fn delete_pending_review(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        review_id: u64,
+    ) -> Result<()> {
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("pulls/{number}/reviews/{review_id}"));
+
+        match self.delete(&url).await {
+            Ok(()) | Err(GitHubError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e).context("Failed to delete pending review"),
+        }
+    }
+
+    /// Submit a pending review with the chosen event and summary body
+    pub async fn submit_review(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        review_id: u64,
+        event: ReviewEvent,
+        body: &str,
+    ) -> Result<()> {
+        let url = self.host.repo_url(
+            owner,
+            repo,
+            &format!("pulls/{number}/reviews/{review_id}/events"),
+        );
+
+        let payload = serde_json::json!({
+            "event": event,
+            "body": body,
+        });
+
+        self.post_json(&url, &payload)
+            .await
+            .context("Failed to submit review")?;
+        Ok(())
+    }
+
+    /// Fetch review comments grouped into threads, with their resolution state when signed in
+    pub async fn get_review_threads(
         &self,
         owner: &str,
         repo: &str,
         number: u64,
-    ) -> Result<PullRequest> {
-        let pr = match &self.client {
-            Some(client) => client.pulls(owner, repo).get(number).await?,
-            None => octocrab::instance().pulls(owner, repo).get(number).await?,
+    ) -> Result<ReviewThreads> {
+        let comments = self.get_review_comments(owner, repo, number).await?;
+        let mut threads = ReviewThread::group(comments);
+        let mut warning = None;
+
+        // The GraphQL API is only available to authenticated users
+        if self.token.is_some() && !threads.is_empty() {
+            match self.get_review_thread_states(owner, repo, number).await {
+                Ok(states) => ReviewThread::attach_states(&mut threads, &states),
+                Err(e) => warning = Some(format!("Failed to load review thread states: {e:#}")),
+            }
+        }
+
+        Ok(ReviewThreads { threads, warning })
+    }
+
+    /// Fetch the GraphQL id and resolution state of every review thread
+    pub async fn get_review_thread_states(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+    ) -> Result<Vec<ReviewThreadState>> {
+        const QUERY: &str = r#"
+            query($owner: String!, $repo: String!, $number: Int!, $cursor: String) {
+              repository(owner: $owner, name: $repo) {
+                pullRequest(number: $number) {
+                  reviewThreads(first: 100, after: $cursor) {
+                    pageInfo { hasNextPage endCursor }
+                    nodes { id isResolved comments(first: 1) { nodes { databaseId } } }
+                  }
+                }
+              }
+            }"#;
+
+        let mut states = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let data = self
+                .graphql(
+                    QUERY,
+                    serde_json::json!({
+                        "owner": owner,
+                        "repo": repo,
+                        "number": number,
+                        "cursor": cursor,
+                    }),
+                )
+                .await
+                .context("Failed to fetch review threads")?;
+
+            let threads = &data["repository"]["pullRequest"]["reviewThreads"];
+            let batch: Vec<ReviewThreadState> = serde_json::from_value(threads["nodes"].clone())
+                .context("Failed to decode review threads")?;
+            states.extend(batch);
+
+            let page_info = &threads["pageInfo"];
+            if !page_info["hasNextPage"].as_bool().unwrap_or(false) {
+                break;
+            }
+            cursor = page_info["endCursor"].as_str().map(str::to_string);
+        }
+
+        Ok(states)
+    }
+
+    /// Reply to a review thread
+    pub async fn reply_to_review_thread(&self, thread_id: &str, body: &str) -> Result<()> {
+        const MUTATION: &str = r#"
+            mutation($thread: ID!, $body: String!) {
+              addPullRequestReviewThreadReply(input: {pullRequestReviewThreadId: $thread, body: $body}) {
+                comment { id }
+              }
+            }"#;
+
+        self.graphql(
+            MUTATION,
+            serde_json::json!({ "thread": thread_id, "body": body }),
+        )
+        .await
+        .context("Failed to reply to review thread")?;
+        Ok(())
+    }
+
+    /// Mark a review thread as resolved or unresolved
+    pub async fn set_review_thread_resolved(&self, thread_id: &str, resolved: bool) -> Result<()> {
+        let mutation = if resolved {
+            "mutation($thread: ID!) { resolveReviewThread(input: {threadId: $thread}) { thread { id } } }"
+        } else {
+            "mutation($thread: ID!) { unresolveReviewThread(input: {threadId: $thread}) { thread { id } } }"
+        };
+
+        self.graphql(mutation, serde_json::json!({ "thread": thread_id }))
+            .await
+            .with_context(|| {
+                if resolved {
+                    "Failed to resolve review thread"
+                } else {
+                    "Failed to unresolve review thread"
+                }
+            })?;
+        Ok(())
+    }
+
+    /// Run a GraphQL query or mutation, returning its `data`
+    async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value, GitHubError> {
+        let payload = serde_json::json!({
+            "query": query,
+            "variables": variables,
+        });
+
+        let mut body = self.post_json(&self.host.graphql_url(), &payload).await?;
+
+        // GraphQL reports most failures with a 200 status and an errors array
+        if let Some(error) = body["errors"].as_array().and_then(|e| e.first()) {
+            let message = error["message"].as_str().unwrap_or("unknown error");
+            return Err(GitHubError::GraphQl(message.to_string()));
+        }
+
+        Ok(body["data"].take())
+    }
+
+    /// GET every page of a list endpoint
+    async fn get_all_pages<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        retention: Retention,
+    ) -> Result<Vec<T>, GitHubError> {
+        const PER_PAGE: usize = 100;
+        let mut items = Vec::new();
+
+        let mut request = self.get(url).query(&[("per_page", PER_PAGE), ("page", 1)]);
+        for page in 2.. {
+            let response = self.get_conditional(request, retention).await?;
+            let batch: Vec<T> = serde_json::from_str(&response.body)?;
+
+            // GitHub links to the next page, otherwise a full page may be followed by another
+            let next = match response.link {
+                Some(ref link) => next_page_url(link).map(|next| self.get(&next)),
+                None if batch.len() < PER_PAGE => None,
+                None => Some(
+                    self.get(url)
+                        .query(&[("per_page", PER_PAGE), ("page", page)]),
+                ),
+            };
+            items.extend(batch);
+            match next {
+                Some(next) => request = next,
+                None => break,
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// GET one page of an endpoint and decode the response
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        page: usize,
+        per_page: usize,
+        retention: Retention,
+    ) -> Result<T, GitHubError> {
+        let request = self
+            .get(url)
+            .query(&[("per_page", per_page), ("page", page)]);
+
+        let response = self.get_conditional(request, retention).await?;
+        Ok(serde_json::from_str(&response.body)?)
+    }
+
+    /// GET an endpoint that isn't paginated and decode the response
+    async fn get_resource<T: serde::de::DeserializeOwned>(
+        &self,
+        url: &str,
+        retention: Retention,
+    ) -> Result<T, GitHubError> {
+        let response = self.get_conditional(self.get(url), retention).await?;
+        Ok(serde_json::from_str(&response.body)?)
+    }
+
+    /// Send a GET request, conditional on the response cached from the last time it was sent.
+    /// GitHub answers with a 304 if nothing changed, which doesn't count against the rate limit.
+    async fn get_conditional(
+        &self,
+        request: reqwest::RequestBuilder,
+        retention: Retention,
+    ) -> Result<CachedResponse, GitHubError> {
+        let (client, request) = request.build_split();
+        let mut request = request?;
+        // The same URL can be asked for as JSON or as a diff, and answered differently
+        // depending on who's asking
+        let accept = request.headers().get(ACCEPT).and_then(|v| v.to_str().ok());
+        let url = format!(
+            "{} {} {}",
+            self.token_fingerprint(),
+            accept.unwrap_or_default(),
+            request.url()
+        );
+
+        let cached = self.responses.get(&url).await;
+        if let Some(ref cached) = cached {
+            let headers = request.headers_mut();
+            if let Some(value) = cached.etag.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(IF_NONE_MATCH, value);
+            }
+            if let Some(value) = cached.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(IF_MODIFIED_SINCE, value);
+            }
+        }
+
+        let response = self
+            .send(reqwest::RequestBuilder::from_parts(client, request))
+            .await?;
+        if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), cached) {
+            return Ok(cached);
+        }
+
+        let header = |name| {
+            let value = response.headers().get(name)?.to_str().ok()?;
+            Some(value.to_string())
+        };
+        let (etag, last_modified, link) = (header(ETAG), header(LAST_MODIFIED), header(LINK));
+        let response = CachedResponse {
+            etag,
+            last_modified,
+            link,
+            body: response.text().await?,
+        };
+
+        if response.etag.is_some() || response.last_modified.is_some() {
+            self.responses
+                .put(url, response.clone(), retention == Retention::Disk)
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// Files changed as read from the unified diff GitHub renders for a commit or pull request,
+    /// `None` if it can't or won't serve it, e.g. because the diff is too large.
+    /// Under the unified strategy, the full file view is left to be built from the contents.
+    async fn get_diff_files(&self, url: &str) -> Result<Option<Vec<FileChange>>> {
+        let diff = match self
+            .get_conditional(self.get_as(url, DIFF_MEDIA_TYPE), Retention::Disk)
+            .await
+        {
+            Ok(response) => response.body,
+            // The file list is still worth a try when the diff is refused or missing
+            Err(
+                GitHubError::Api { .. } | GitHubError::NotFound(_) | GitHubError::AuthFailed { .. },
+            ) => return Ok(None),
+            Err(e) => return Err(e).context("Failed to fetch the diff"),
+        };
+
+        let mut files = DiffParser::split_unified_diff(&diff)?;
+        if self.diff_strategy == DiffStrategy::Unified {
+            for file in &mut files {
+                file.diff_content = None;
+            }
+        }
+        Ok(Some(files))
+    }
+
+    /// Fetch a file at a ref, `None` if it doesn't exist there.
+    /// The raw host is tried first as it doesn't count against the rate limit, but it refuses
+    /// fine-grained tokens and organizations that enforce SSO. The contents API works with any
+    /// token that can read the repository, and the git blobs API is the last resort.
+    async fn fetch_file_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        r#ref: &str,
+        blob_sha: Option<&str>,
+    ) -> Result<Option<String>, GitHubError> {
+        if self.diff_strategy == DiffStrategy::Raw {
+            let url = self.host.raw_url(owner, repo, r#ref, path);
+            let mut request = self.http.get(&url);
+            if let Some(ref token) = self.token {
+                request = request.header("Authorization", format!("Bearer {token}"));
+            }
+
+            match self.send(request).await {
+                Ok(response) => return Ok(Some(response.text().await?)),
+                // The raw host answers 404 for files it won't serve, not only missing ones
+                Err(GitHubError::NotFound(_) | GitHubError::AuthFailed { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let url = self.host.contents_url(owner, repo, path);
+        let request = self.get_as(&url, RAW_MEDIA_TYPE).query(&[("ref", r#ref)]);
+        let error = match self.send(request).await {
+            Ok(response) => return Ok(Some(response.text().await?)),
+            Err(GitHubError::NotFound(_)) => return Ok(None),
+            Err(e) => e,
         };
 
+        if !matches!(
+            error,
+            GitHubError::AuthFailed { .. } | GitHubError::Api { .. }
+        ) {
+            return Err(error);
+        }
+        let blob_sha = match blob_sha {
+            Some(sha) => Some(sha.to_string()),
+            None => {
+                let key = FileCacheKey {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    path: path.to_string(),
+                    sha: r#ref.to_string(),
+                };
+                self.list_blob_sha(&key).await
+            }
+        };
+        let Some(sha) = blob_sha else {
+            return Err(error);
+        };
+
+        let url = self.host.repo_url(owner, repo, &format!("git/blobs/{sha}"));
+        let response = self.send(self.get_as(&url, RAW_MEDIA_TYPE)).await?;
+        Ok(Some(response.text().await?))
+    }
+
+    /// Remember the blob SHA of a file at a ref, to fetch and cache it by its blob
+    fn remember_blob_sha(&self, owner: &str, repo: &str, path: &str, r#ref: &str, sha: &str) {
+        let key = FileCacheKey {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            path: path.to_string(),
+            sha: r#ref.to_string(),
+        };
+        self.blob_shas.lock().unwrap().put(key, sha.to_string());
+    }
+
+    /// Blob SHA of a file at a ref, if it was listed
+    fn blob_sha(&self, key: &FileCacheKey) -> Option<String> {
+        self.blob_shas.lock().unwrap().get(key).cloned()
+    }
+
+    /// Blob SHA of a file at a ref, listing the files of the pull requests and commits that were
+    /// read from their diffs to find it. That costs requests, so it's only done as a last resort.
+    async fn list_blob_sha(&self, key: &FileCacheKey) -> Option<String> {
+        let listings: Vec<FileListing> = {
+            let mut unlisted = self.unlisted.lock().unwrap();
+            let (wanted, rest) = std::mem::take(&mut *unlisted)
+                .into_iter()
+                .partition(|listing| listing.may_list(key));
+            *unlisted = rest;
+            wanted
+        };
+        if listings.is_empty() {
+            return None;
+        }
+
+        // Blob SHAs only make caching and fallbacks better, so failing to list them is fine
+        for listing in listings {
+            let _ = match listing {
+                FileListing::PullRequest { number, .. } => {
+                    self.list_pr_files(&key.owner, &key.repo, number).await
+                }
+                FileListing::Commit { ref sha, .. } => {
+                    self.list_commit_files(&key.owner, &key.repo, sha).await
+                }
+            };
+        }
+        self.blob_shas.lock().unwrap().get(key).cloned()
+    }
+
+    /// Start a GET request to the GitHub API, authenticated if there's a token
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        self.get_as(url, JSON_MEDIA_TYPE)
+    }
+
+    /// Start a GET request for a response of the `accept` media type
+    fn get_as(&self, url: &str, accept: &str) -> reqwest::RequestBuilder {
+        let mut request = self.http.get(url).header(ACCEPT, accept);
+
+        if let Some(ref token) = self.token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        request
+    }
+
+    /// Identifies the token in cache keys without revealing it
+    fn token_fingerprint(&self) -> String {
+        match self.token {
+            Some(ref token) => format!("{:016x}", fnv1a(token.as_bytes())),
+            None => "anonymous".to_string(),
+        }
+    }
+
+    /// Send a request, retrying with backoff while it fails for reasons that pass,
+    /// like network hiccups, server errors and short rate limits. Requests that change
+    /// something are only retried when they can't have reached GitHub.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, GitHubError> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let idempotent = matches!(request.method(), &Method::GET | &Method::HEAD);
+
+        let mut retries = 0;
+        loop {
+            let attempt = request.try_clone().ok_or(GitHubError::StreamedBody)?;
+
+            match self
+                .send_once(reqwest::RequestBuilder::from_parts(client.clone(), attempt))
+                .await
+            {
+                Err(e) if e.is_retryable(idempotent) && retries < MAX_RETRIES => {
+                    retries += 1;
+                    tokio::time::sleep(backoff(retries)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Send a request once, turning unsuccessful responses into errors.
+    /// Rate limits reported with the response are recorded, and waited for by later requests.
+    async fn send_once(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, GitHubError> {
+        self.rate_limiter.wait().await;
+        let response = request.send().await?;
+        let status = response.status();
+
+        if let Some(retry_after) = self.rate_limiter.observe(status, response.headers()) {
+            return Err(GitHubError::RateLimited {
+                retry_after,
+                authenticated: self.token.is_some(),
+            });
+        }
+        // Conditional requests get a 304 when the cached response is still current
+        if status.is_success() || status == StatusCode::NOT_MODIFIED {
+            return Ok(response);
+        }
+
+        let url = response.url().to_string();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let message = body["message"].as_str().map(str::to_string);
+        Err(GitHubError::from_status(status, &url, message))
+    }
+
+    /// POST a JSON payload to the GitHub API, returning the decoded response
+    async fn post_json<T: serde::Serialize + ?Sized>(
+        &self,
+        url: &str,
+        payload: &T,
+    ) -> Result<serde_json::Value, GitHubError> {
+        let token = self.token.as_ref().ok_or(GitHubError::TokenRequired)?;
+
+        let request = self
+            .http
+            .post(url)
+            .header(ACCEPT, JSON_MEDIA_TYPE)
+            .header("Authorization", format!("Bearer {token}"))
+            .json(payload);
+        let response = self.send(request).await?;
+
+        // Some endpoints answer without a body
+        Ok(response.json().await.unwrap_or_default())
+    }
+
+    /// DELETE a resource from the GitHub API
+    async fn delete(&self, url: &str) -> Result<(), GitHubError> {
+        let token = self.token.as_ref().ok_or(GitHubError::TokenRequired)?;
+
+        let request = self
+            .http
+            .delete(url)
+            .header(ACCEPT, JSON_MEDIA_TYPE)
+            .header("Authorization", format!("Bearer {token}"));
+        self.send(request).await?;
+        Ok(())
+    }
+}
+
+/// URL of the next page in a `Link` header, like `<https://...&page=2>; rel="next"`
+fn next_page_url(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+/// Where a response is cached for conditional requests
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Retention {
+    /// In memory only, for conversations and CI results that aren't worth keeping around
+    Session,
+    /// On disk too, for the pull request, its commits and files, which are loaded on every run
+    Disk,
+}
+
+/// Delay before retrying a request: exponential backoff with random jitter,
+/// so that concurrent requests that failed together don't retry together
+fn backoff(retry: usize) -> Duration {
+    let base = RETRY_BASE_DELAY * 2u32.pow(retry as u32 - 1);
+    // Each RandomState is seeded differently, which is random enough for jitter
+    let random = RandomState::new().build_hasher().finish();
+    base + base.mul_f64((random % 1000) as f64 / 1000.0)
+}
+
+#[async_trait]
+impl Forge for GitHubClient {
+    async fn get_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<PullRequest> {
+        let url = self.host.repo_url(owner, repo, &format!("pulls/{number}"));
+        let pr: octocrab::models::pulls::PullRequest =
+            self.get_resource(&url, Retention::Disk).await?;
+
         // Map octocrab types to our models
         Ok(PullRequest {
             number: pr.number,
@@ -105,33 +939,13 @@ impl GitHubClient {
         })
     }
 
-    pub async fn get_pr_commits(
-        &self,
-        owner: &str,
-        repo: &str,
-        number: u64,
-    ) -> Result<Vec<Commit>> {
-        // Fetch actual commits from the PR
-        let commits = match &self.client {
-            Some(client) => {
-                client
-                    .pulls(owner, repo)
-                    .pr_commits(number)
-                    .per_page(250) // Get up to 250 commits
-                    .send()
-                    .await?
-            }
-            None => {
-                // Use anonymous client
-                let client = octocrab::instance();
-                client
-                    .pulls(owner, repo)
-                    .pr_commits(number)
-                    .per_page(250)
-                    .send()
-                    .await?
-            }
-        };
+    async fn get_pr_commits(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<Commit>> {
+        // GitHub lists up to 250 commits
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("pulls/{number}/commits"));
+        let commits: Vec<octocrab::models::repos::RepoCommit> =
+            self.get_all_pages(&url, Retention::Disk).await?;
 
         let mut result = Vec::new();
         for commit in commits {
@@ -194,125 +1008,156 @@ impl GitHubClient {
         Ok(result)
     }
 
-    pub async fn get_pr_files(
-        &self,
-        owner: &str,
-        repo: &str,
-        number: u64,
-    ) -> Result<Vec<FileChange>> {
-        let files = match &self.client {
-            Some(client) => client.pulls(owner, repo).list_files(number).await?,
-            None => {
-                octocrab::instance()
-                    .pulls(owner, repo)
-                    .list_files(number)
-                    .await?
+    async fn get_pr_files(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<FileChange>> {
+        if self.diff_strategy != DiffStrategy::Raw {
+            let url = self.host.repo_url(owner, repo, &format!("pulls/{number}"));
+            if let Some(files) = self.get_diff_files(&url).await? {
+                self.defer_listing(FileListing::PullRequest {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    number,
+                });
+                return Ok(files);
             }
-        };
-
-        let mut result = Vec::new();
-        for file in files {
-            use octocrab::models::repos::DiffEntryStatus;
-            let status = match file.status {
-                DiffEntryStatus::Added => FileStatus::Added,
-                DiffEntryStatus::Removed => FileStatus::Deleted,
-                DiffEntryStatus::Modified => FileStatus::Modified,
-                DiffEntryStatus::Renamed => FileStatus::Renamed,
-                DiffEntryStatus::Copied => FileStatus::Copied,
-                _ => FileStatus::Modified,
-            };
-
-            result.push(FileChange {
-                filename: file.filename.clone(),
-                status,
-                additions: file.additions as u32,
-                deletions: file.deletions as u32,
-                patch: file.patch.clone(),
-                raw_content: None,
-                diff_content: None,
-            });
         }
 
-        Ok(result)
+        self.list_pr_files(owner, repo, number).await
     }
 
-    pub async fn get_file_content(
+    async fn get_file_content(
         &self,
         owner: &str,
         repo: &str,
         path: &str,
         r#ref: &str,
     ) -> Result<String> {
-        // Check cache first
-        let cache_key = FileCacheKey {
+        // Check cache first. Files with a known blob are cached by it, as the same content
+        // often shows up at several commits.
+        let ref_key = FileCacheKey {
             owner: owner.to_string(),
             repo: repo.to_string(),
             path: path.to_string(),
             sha: r#ref.to_string(),
         };
+        let blob_sha = self.blob_sha(&ref_key);
+        let cache_key = match blob_sha {
+            Some(ref sha) => FileCacheKey::blob(owner, repo, sha),
+            None => ref_key.clone(),
+        };
 
         if let Some(cached_content) = self.cache.get(&cache_key).await {
             return Ok(cached_content);
         }
 
         // Not in cache, fetch from GitHub
-        let url = format!(
-            "https://raw.githubusercontent.com/{owner}/{repo}/{ref}/{path}",
-            r#ref = r#ref
-        );
-
-        let client = reqwest::Client::new();
-        let mut request = client.get(&url);
-
-        if let Some(ref token) = self.token {
-            request = request.header("Authorization", format!("Bearer {token}"));
-        }
-
-        let response = request
-            .send()
+        let content = self
+            .fetch_file_content(owner, repo, path, r#ref, blob_sha.as_deref())
             .await
-            .context("Failed to fetch file content")?;
-
-        if !response.status().is_success() {
-            // File might not exist in this ref (e.g., deleted file)
+            .with_context(|| format!("Failed to fetch {path} at {ref}"))?;
+        // File might not exist in this ref (e.g., deleted file)
+        let Some(content) = content else {
             return Ok(String::new());
-        }
-
-        let content = response
-            .text()
-            .await
-            .context("Failed to read file content")?;
+        };
 
-        // Cache the content
+        // Cache the content, by its blob if it was only found while fetching
+        let cache_key = match self.blob_sha(&ref_key) {
+            Some(sha) if blob_sha.is_none() => FileCacheKey::blob(owner, repo, &sha),
+            _ => cache_key,
+        };
         self.cache.put(cache_key, content.clone()).await;
 
         Ok(content)
     }
 
-    pub async fn get_commit_files(
+    async fn get_commit_files(
         &self,
         owner: &str,
         repo: &str,
         sha: &str,
     ) -> Result<Vec<FileChange>> {
-        // Use the GitHub API directly to fetch commit details
-        let url = format!("https://api.github.com/repos/{owner}/{repo}/commits/{sha}");
+        let url = self.host.repo_url(owner, repo, &format!("commits/{sha}"));
+        if self.diff_strategy != DiffStrategy::Raw {
+            if let Some(files) = self.get_diff_files(&url).await? {
+                self.defer_listing(FileListing::Commit {
+                    owner: owner.to_string(),
+                    repo: repo.to_string(),
+                    sha: sha.to_string(),
+                });
+                return Ok(files);
+            }
+        }
 
-        let client = reqwest::Client::new();
-        let mut request = client
-            .get(&url)
-            .header("Accept", "application/vnd.github.v3+json")
-            .header("User-Agent", "revu");
+        self.list_commit_files(owner, repo, sha).await
+    }
+}
 
-        if let Some(ref token) = self.token {
-            request = request.header("Authorization", format!("Bearer {token}"));
+impl GitHubClient {
+    /// Files changed by a pull request, from its file listing
+    async fn list_pr_files(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<FileChange>> {
+        // GitHub lists up to 3000 files
+        let url = self
+            .host
+            .repo_url(owner, repo, &format!("pulls/{number}/files"));
+        let files: Vec<octocrab::models::repos::DiffEntry> =
+            self.get_all_pages(&url, Retention::Disk).await?;
+
+        let mut result = Vec::new();
+        for file in files {
+            use octocrab::models::repos::DiffEntryStatus;
+            let status = match file.status {
+                DiffEntryStatus::Added => FileStatus::Added,
+                DiffEntryStatus::Removed => FileStatus::Deleted,
+                DiffEntryStatus::Modified => FileStatus::Modified,
+                DiffEntryStatus::Renamed => FileStatus::Renamed,
+                DiffEntryStatus::Copied => FileStatus::Copied,
+                _ => FileStatus::Modified,
+            };
+
+            // The listed blob is the file at the head of the pull request
+            let head = file
+                .contents_url
+                .query_pairs()
+                .find(|(name, _)| name == "ref");
+            if let (Some(sha), Some((_, head))) = (&file.sha, head) {
+                if status != FileStatus::Deleted {
+                    self.remember_blob_sha(owner, repo, &file.filename, &head, sha);
+                }
+            }
+
+            result.push(FileChange {
+                filename: file.filename.clone(),
+                status,
+                additions: file.additions as u32,
+                deletions: file.deletions as u32,
+                patch: file.patch.clone(),
+                raw_content: None,
+                diff_content: None,
+            });
         }
 
-        let response = request.send().await?;
-        let commit_data: serde_json::Value = response.json().await?;
+        Ok(result)
+    }
+
+    /// Files changed by a commit, from its details
+    async fn list_commit_files(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<Vec<FileChange>> {
+        let url = self.host.repo_url(owner, repo, &format!("commits/{sha}"));
+
+        // Use the GitHub API directly to fetch commit details.
+        // Its files are paginated, up to 3000 of them.
+        const PER_PAGE: usize = 100;
 
         let mut result = Vec::new();
-        if let Some(files) = commit_data["files"].as_array() {
+        for page in 1.. {
+            let commit_data: serde_json::Value =
+                self.get_json(&url, page, PER_PAGE, Retention::Disk).await?;
+            let files = commit_data["files"].as_array().cloned().unwrap_or_default();
+            let is_last = files.len() < PER_PAGE;
+
             for file in files {
                 let status_str = file["status"].as_str().unwrap_or("modified");
                 let status = match status_str {
@@ -324,8 +1169,14 @@ impl GitHubClient {
                     _ => FileStatus::Modified,
                 };
 
+                let filename = file["filename"].as_str().unwrap_or("").to_string();
+                let blob_sha = file["sha"].as_str();
+                if let Some(blob_sha) = blob_sha.filter(|_| status != FileStatus::Deleted) {
+                    self.remember_blob_sha(owner, repo, &filename, sha, blob_sha);
+                }
+
                 result.push(FileChange {
-                    filename: file["filename"].as_str().unwrap_or("").to_string(),
+                    filename,
                     status,
                     additions: file["additions"].as_u64().unwrap_or(0) as u32,
                     deletions: file["deletions"].as_u64().unwrap_or(0) as u32,
@@ -334,8 +1185,48 @@ impl GitHubClient {
                     diff_content: None,
                 });
             }
+
+            if is_last {
+                break;
+            }
         }
 
         Ok(result)
     }
+
+    /// Remember that the files of a diff weren't listed
+    fn defer_listing(&self, listing: FileListing) {
+        let mut unlisted = self.unlisted.lock().unwrap();
+        if !unlisted.contains(&listing) {
+            unlisted.push(listing);
+        }
+    }
+}
+
+/// Listing of the files a pull request or commit changed
+#[derive(Debug, Clone, PartialEq)]
+enum FileListing {
+    PullRequest {
+        owner: String,
+        repo: String,
+        number: u64,
+    },
+    Commit {
+        owner: String,
+        repo: String,
+        sha: String,
+    },
+}
+
+impl FileListing {
+    /// Whether the listing may hold the blob SHA of a file. Pull requests list the files at
+    /// their head, which isn't known here.
+    fn may_list(&self, file: &FileCacheKey) -> bool {
+        match self {
+            Self::PullRequest { owner, repo, .. } => *owner == file.owner && *repo == file.repo,
+            Self::Commit { owner, repo, sha } => {
+                *owner == file.owner && *repo == file.repo && *sha == file.sha
+            }
+        }
+    }
 }
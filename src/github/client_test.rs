@@ -1,6 +1,33 @@
 #[cfg(test)]
 mod tests {
+    use super::super::error::GitHubError;
     use super::super::*;
+    use crate::{
+        cache::{FileContentCache, ResponseCache},
+        forge::{Forge, ForgeKind},
+        settings::DiffStrategy,
+    };
+
+    /// Client for the GitHub API mocked by `server`
+    async fn mock_client(server: &mockito::ServerGuard) -> GitHubClient {
+        client_with_token(server, None).await
+    }
+
+    async fn client_with_token(server: &mockito::ServerGuard, token: Option<&str>) -> GitHubClient {
+        let host = GitHubHost {
+            web_host: "github.example.com".to_string(),
+            api_url: server.url(),
+        };
+        GitHubClient::new(
+            token.map(str::to_string),
+            host,
+            reqwest::Client::new(),
+            FileContentCache::default(),
+            ResponseCache::default(),
+        )
+        .await
+        .unwrap()
+    }
 
     #[test]
     fn test_parse_pr_url() {
@@ -11,6 +38,26 @@ mod tests {
         assert_eq!(parsed.owner, "rust-lang");
         assert_eq!(parsed.repo, "rust");
         assert_eq!(parsed.number, 12345);
+        assert_eq!(parsed.host.as_deref(), Some("github.com"));
+
+        // GitHub Enterprise Server URLs carry their own host
+        let parsed =
+            GitHubClient::parse_pr_url("https://GHE.example.com/platform/api/pull/7/files")
+                .unwrap();
+        assert_eq!(parsed.host.as_deref(), Some("ghe.example.com"));
+        assert_eq!(parsed.owner, "platform");
+        assert_eq!(parsed.repo, "api");
+        assert_eq!(parsed.number, 7);
+        assert_eq!(parsed.forge, ForgeKind::GitHub);
+
+        // Gitea and Forgejo list pull requests under /pulls/
+        let parsed =
+            GitHubClient::parse_pr_url("https://git.example.com/tools/deployer/pulls/7").unwrap();
+        assert_eq!(parsed.forge, ForgeKind::Gitea);
+        assert_eq!(parsed.host.as_deref(), Some("git.example.com"));
+        assert_eq!(parsed.owner, "tools");
+        assert_eq!(parsed.repo, "deployer");
+        assert_eq!(parsed.number, 7);
 
         // Test PR number parsing
         std::env::set_var("GITHUB_OWNER", "test-owner");
@@ -21,6 +68,265 @@ mod tests {
         assert_eq!(parsed.owner, "test-owner");
         assert_eq!(parsed.repo, "test-repo");
         assert_eq!(parsed.number, 789);
+        assert!(parsed.host.is_none());
+    }
+
+    #[test]
+    fn test_review_payload_serialization() {
+        let event = serde_json::to_value(ReviewEvent::RequestChanges).unwrap();
+        assert_eq!(event, "REQUEST_CHANGES");
+
+        let comment = NewReviewComment {
+            body: "Looks off".to_string(),
+            commit_id: "abc123".to_string(),
+            path: "src/main.rs".to_string(),
+            line: 42,
+            side: DiffSide::Left,
+            start_line: None,
+            start_side: None,
+        };
+        let draft = serde_json::to_value(DraftReviewComment::from(&comment)).unwrap();
+        assert_eq!(draft["side"], "LEFT");
+        assert_eq!(draft["line"], 42);
+        // Review comments inherit the commit from the review itself
+        assert!(draft.get("commit_id").is_none());
+        // Single-line comments leave out the range start
+        assert!(draft.get("start_line").is_none());
+
+        let range = NewReviewComment {
+            start_line: Some(40),
+            start_side: Some(DiffSide::Right),
+            side: DiffSide::Right,
+            ..comment
+        };
+        let payload = serde_json::to_value(&range).unwrap();
+        assert_eq!(payload["start_line"], 40);
+        assert_eq!(payload["start_side"], "RIGHT");
+        assert_eq!(payload["line"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_review_threads_without_states() {
+        let mut server = mockito::Server::new_async().await;
+        let comment = serde_json::json!({
+            "id": 42,
+            "path": "src/main.rs",
+            "body": "Nit",
+            "user": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "line": 3,
+            "original_line": 3,
+            "side": "RIGHT",
+            "commit_id": "abc",
+            "original_commit_id": "abc",
+            "in_reply_to_id": null
+        });
+        server
+            .mock("GET", "/repos/owner/repo/pulls/1/comments")
+            .match_query(mockito::Matcher::Any)
+            .with_body(serde_json::json!([comment]).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/graphql")
+            .with_body(r#"{"errors": [{"message": "Something went wrong"}]}"#)
+            .create_async()
+            .await;
+
+        let client = client_with_token(&server, Some("token")).await;
+        let threads = client.get_review_threads("owner", "repo", 1).await.unwrap();
+        assert_eq!(threads.threads.len(), 1);
+        assert!(!threads.threads[0].is_resolved);
+        assert!(threads.warning.unwrap().contains("Something went wrong"));
+    }
+
+    #[test]
+    fn test_review_thread_states_attach_to_threads() {
+        let nodes = serde_json::json!([
+            {
+                "id": "PRRT_kwDOAbc",
+                "isResolved": true,
+                "comments": { "nodes": [{ "databaseId": 42 }] }
+            }
+        ]);
+        let states: Vec<ReviewThreadState> = serde_json::from_value(nodes).unwrap();
+        assert_eq!(states[0].root_comment_id(), Some(42));
+
+        let comment: ReviewComment = serde_json::from_value(serde_json::json!({
+            "id": 42,
+            "path": "src/main.rs",
+            "body": "Nit",
+            "user": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "line": 3,
+            "original_line": 3,
+            "side": "RIGHT",
+            "commit_id": "abc",
+            "original_commit_id": "abc",
+            "in_reply_to_id": null
+        }))
+        .unwrap();
+
+        let mut threads = ReviewThread::group(vec![comment]);
+        assert!(!threads[0].is_resolved);
+
+        ReviewThread::attach_states(&mut threads, &states);
+        assert!(threads[0].is_resolved);
+        assert_eq!(threads[0].node_id.as_deref(), Some("PRRT_kwDOAbc"));
+    }
+
+    #[test]
+    fn test_timeline_from_events() {
+        let events = serde_json::json!([
+            {
+                "event": "committed",
+                "message": "Add parser\n\nLonger description",
+                "author": { "name": "alice" },
+                "committer": { "date": "2024-01-01T10:00:00Z" }
+            },
+            {
+                "event": "committed",
+                "message": "Fix tests",
+                "author": { "name": "alice" },
+                "committer": { "date": "2024-01-01T10:05:00Z" }
+            },
+            {
+                "event": "line-commented",
+                "comments": []
+            },
+            {
+                "event": "reviewed",
+                "state": "changes_requested",
+                "body": "Needs work",
+                "user": { "login": "bob" },
+                "submitted_at": "2024-01-02T09:00:00Z"
+            },
+            {
+                "event": "head_ref_force_pushed",
+                "actor": { "login": "alice" },
+                "commit_id": "deadbeef",
+                "created_at": "2024-01-02T12:00:00Z"
+            },
+            {
+                "event": "review_requested",
+                "actor": { "login": "alice" },
+                "requested_team": { "name": "core" },
+                "created_at": "2024-01-02T12:01:00Z"
+            },
+            {
+                "event": "commented",
+                "body": "Thanks!",
+                "user": { "login": "bob" },
+                "created_at": "2024-01-03T08:00:00Z"
+            }
+        ]);
+        let items = TimelineItem::from_events(events.as_array().unwrap());
+
+        assert_eq!(items.len(), 5);
+        assert_eq!(
+            items[0].kind,
+            TimelineKind::Push {
+                commits: vec!["Add parser".to_string(), "Fix tests".to_string()]
+            }
+        );
+        assert_eq!(items[0].actor.as_deref(), Some("alice"));
+        assert_eq!(
+            items[1].kind,
+            TimelineKind::Review {
+                state: "changes_requested".to_string(),
+                body: "Needs work".to_string()
+            }
+        );
+        assert_eq!(
+            items[2].kind,
+            TimelineKind::ForcePush {
+                commit_id: Some("deadbeef".to_string())
+            }
+        );
+        assert_eq!(
+            items[3].kind,
+            TimelineKind::ReviewRequest {
+                reviewer: "core".to_string(),
+                requested: true
+            }
+        );
+        assert_eq!(items[4].actor.as_deref(), Some("bob"));
+    }
+
+    #[test]
+    fn test_commit_checks_state() {
+        let runs: CheckRunList = serde_json::from_value(serde_json::json!({
+            "total_count": 3,
+            "check_runs": [
+                {
+                    "id": 116,
+                    "name": "test",
+                    "status": "completed",
+                    "conclusion": "success",
+                    "output": { "title": null, "summary": null }
+                },
+                {
+                    "id": 99,
+                    "name": "clippy",
+                    "status": "completed",
+                    "conclusion": "failure",
+                    "output": { "title": "2 errors", "summary": "" }
+                },
+                {
+                    "id": 100,
+                    "name": "docs",
+                    "status": "completed",
+                    "conclusion": "skipped",
+                    "output": { "title": null, "summary": null }
+                }
+            ]
+        }))
+        .unwrap();
+        let combined: CombinedStatus = serde_json::from_value(serde_json::json!({
+            "state": "pending",
+            "statuses": [
+                { "context": "ci/jenkins", "state": "pending", "description": "Build queued" }
+            ]
+        }))
+        .unwrap();
+
+        let mut checks = CommitChecks {
+            check_runs: runs.check_runs,
+            statuses: combined.statuses,
+        };
+        assert_eq!(checks.state(), Some(CheckState::Failure));
+
+        let entries = checks.entries();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["clippy", "ci/jenkins", "test", "docs"]);
+        assert_eq!(entries[0].summary.as_deref(), Some("2 errors"));
+
+        // Skipped checks don't count against a commit
+        checks.check_runs.remove(1);
+        checks.statuses.clear();
+        assert_eq!(checks.state(), Some(CheckState::Success));
+
+        assert_eq!(CommitChecks::default().state(), None);
+    }
+
+    #[test]
+    fn test_check_annotation_deserialize() {
+        let annotation: CheckAnnotation = serde_json::from_value(serde_json::json!({
+            "path": "src/main.rs",
+            "start_line": 12,
+            "end_line": 12,
+            "start_column": 9,
+            "end_column": 10,
+            "annotation_level": "failure",
+            "title": "unused variable: `x`",
+            "message": "unused variable: `x`",
+            "raw_details": null
+        }))
+        .unwrap();
+        assert_eq!(annotation.annotation_level, AnnotationLevel::Failure);
+        assert!(annotation.check_name.is_empty());
     }
 
     #[test]
@@ -28,4 +334,501 @@ mod tests {
         let result = GitHubClient::parse_pr_url("not-a-valid-url");
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_pr_files_follow_pages() {
+        let mut server = mockito::Server::new_async().await;
+        let files = |range: std::ops::Range<usize>| {
+            let files: Vec<_> = range
+                .map(|i| {
+                    serde_json::json!({
+                        "sha": null,
+                        "filename": format!("src/file{i}.rs"),
+                        "status": "modified",
+                        "additions": 1,
+                        "deletions": 0,
+                        "changes": 1,
+                        "blob_url": null,
+                        "raw_url": null,
+                        "contents_url": format!("https://api.github.com/contents/src/file{i}.rs")
+                    })
+                })
+                .collect();
+            serde_json::to_string(&files).unwrap()
+        };
+        let path = "/repos/owner/repo/pulls/1/files";
+        server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Exact("per_page=100&page=1".to_string()))
+            .with_header("content-type", "application/json")
+            .with_body(files(0..100))
+            .create_async()
+            .await;
+        server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Exact("per_page=100&page=2".to_string()))
+            .with_header("content-type", "application/json")
+            .with_body(files(100..130))
+            .create_async()
+            .await;
+
+        let client = mock_client(&server).await;
+        let files = client.get_pr_files("owner", "repo", 1).await.unwrap();
+        assert_eq!(files.len(), 130);
+        assert_eq!(files[129].filename, "src/file129.rs");
+    }
+
+    #[tokio::test]
+    async fn test_pages_follow_link_header() {
+        let mut server = mockito::Server::new_async().await;
+        let comment = |id: u64| {
+            serde_json::json!({
+                "id": id,
+                "path": "src/main.rs",
+                "body": "Nit",
+                "user": null,
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z",
+                "line": id,
+                "original_line": id,
+                "side": "RIGHT",
+                "commit_id": "abc",
+                "original_commit_id": "abc",
+                "in_reply_to_id": null
+            })
+        };
+        let path = "/repos/owner/repo/pulls/1/comments";
+        // Short pages are followed too as long as GitHub links to the next one
+        server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Exact("per_page=100&page=1".to_string()))
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{url}{path}?per_page=100&cursor=2>; rel="next", <{url}{path}?per_page=100&cursor=2>; rel="last""#,
+                    url = server.url()
+                ),
+            )
+            .with_body(serde_json::json!([comment(1)]).to_string())
+            .create_async()
+            .await;
+        let next = server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Exact("per_page=100&cursor=2".to_string()))
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{}{path}?per_page=100&page=1>; rel="prev""#,
+                    server.url()
+                ),
+            )
+            .with_body(serde_json::json!([comment(2)]).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = mock_client(&server).await;
+        let threads = client.get_review_threads("owner", "repo", 1).await.unwrap();
+        assert_eq!(threads.threads.len(), 2);
+        next.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_typed_and_retried() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/repo/pulls/1/commits")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .with_body(r#"{"message": "Bad credentials"}"#)
+            .create_async()
+            .await;
+        // A server error passes on the next try
+        let flaky = server
+            .mock("GET", "/repos/owner/repo/pulls/2/commits")
+            .match_query(mockito::Matcher::Any)
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/pulls/2/commits")
+            .match_query(mockito::Matcher::Any)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = mock_client(&server).await;
+
+        let error = client.get_pr_commits("owner", "repo", 1).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<GitHubError>(),
+            Some(GitHubError::AuthFailed { .. })
+        ));
+        assert!(format!("{error:#}").contains("Bad credentials"));
+
+        assert!(client
+            .get_pr_commits("owner", "repo", 2)
+            .await
+            .unwrap()
+            .is_empty());
+        flaky.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_checks_of_failed_commits_are_left_out() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/repo/commits/good/check-runs")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"total_count": 0, "check_runs": []}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/commits/good/status")
+            .match_query(mockito::Matcher::Any)
+            .with_body(r#"{"statuses": []}"#)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/commits/gone/check-runs")
+            .match_query(mockito::Matcher::Any)
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let client = mock_client(&server).await;
+        let shas = ["good".to_string(), "gone".to_string()];
+        let checks = client.get_checks_for_commits("owner", "repo", &shas).await;
+        assert_eq!(checks.len(), 1);
+        assert!(checks.contains_key("good"));
+    }
+
+    #[tokio::test]
+    async fn test_posts_are_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        // The comment may have been posted before the gateway failed
+        let comment = server
+            .mock("POST", "/repos/owner/repo/issues/1/comments")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = client_with_token(&server, Some("token")).await;
+        let error = client
+            .create_issue_comment("owner", "repo", 1, "LGTM")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<GitHubError>(),
+            Some(GitHubError::Api { .. })
+        ));
+        comment.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unified_diff_strategy() {
+        let mut server = mockito::Server::new_async().await;
+        let diff = "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n";
+        server
+            .mock("GET", "/repos/owner/repo/pulls/1")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_body(diff)
+            .create_async()
+            .await;
+        // Diffs that are too large aren't rendered, so the files are listed instead
+        server
+            .mock("GET", "/repos/owner/repo/pulls/2")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_status(406)
+            .with_body(r#"{"message": "Sorry, the diff exceeded the maximum number of files"}"#)
+            .create_async()
+            .await;
+        // Some tokens and proxies can't get diffs but can list the files
+        server
+            .mock("GET", "/repos/owner/repo/pulls/3")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_status(403)
+            .with_body(r#"{"message": "Resource not accessible by integration"}"#)
+            .create_async()
+            .await;
+        let listed = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/repos/owner/repo/pulls/[23]/files$".to_string()),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_body("[]")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = mock_client(&server)
+            .await
+            .with_diff_strategy(DiffStrategy::Unified);
+        let files = client.get_pr_files("owner", "repo", 1).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].additions, files[0].deletions), (1, 0));
+        // The full file view is built from the contents once the file is opened
+        assert!(files[0].patch.is_some() && files[0].diff_content.is_none());
+
+        let client = client.with_diff_strategy(DiffStrategy::Hunks);
+        let files = client.get_pr_files("owner", "repo", 1).await.unwrap();
+        assert!(files[0].diff_content.is_some());
+
+        assert!(client
+            .get_pr_files("owner", "repo", 2)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(client
+            .get_pr_files("owner", "repo", 3)
+            .await
+            .unwrap()
+            .is_empty());
+        listed.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_diff_files_are_not_listed() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/repo/pulls/1")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_body("diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1,2 @@\n fn a() {}\n+fn b() {}\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/contents/src/lib.rs")
+            .match_query(mockito::Matcher::Any)
+            .with_body("fn a() {}\nfn b() {}\n")
+            .create_async()
+            .await;
+        let listed = server
+            .mock("GET", "/repos/owner/repo/pulls/1/files")
+            .match_query(mockito::Matcher::Any)
+            .with_body("[]")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = mock_client(&server)
+            .await
+            .with_diff_strategy(DiffStrategy::Unified);
+        client.get_pr_files("owner", "repo", 1).await.unwrap();
+        let content = client
+            .get_file_content("owner", "repo", "src/lib.rs", "head")
+            .await
+            .unwrap();
+        assert_eq!(content, "fn a() {}\nfn b() {}\n");
+        // Files are only listed for their blobs when the contents API can't serve them
+        listed.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unified_diff_request_count() {
+        let mut server = mockito::Server::new_async().await;
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n\
+diff --git a/b.rs b/b.rs\n--- a/b.rs\n+++ b/b.rs\n@@ -1 +1 @@\n-c\n+d\n";
+        let diffed = server
+            .mock("GET", "/repos/owner/repo/pulls/1")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_body(diff)
+            .expect(1)
+            .create_async()
+            .await;
+        let contents = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/repos/owner/repo/contents/[ab]\.rs$".to_string()),
+            )
+            .match_query(mockito::Matcher::Any)
+            .with_body("content\n")
+            .expect(4)
+            .create_async()
+            .await;
+        let listed = server
+            .mock("GET", "/repos/owner/repo/pulls/1/files")
+            .match_query(mockito::Matcher::Any)
+            .with_body("[]")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = mock_client(&server)
+            .await
+            .with_diff_strategy(DiffStrategy::Unified);
+        let files = client.get_pr_files("owner", "repo", 1).await.unwrap();
+        assert_eq!(files.len(), 2);
+
+        // One request for the diff, then one for each side of each file as it's opened, twice
+        for _ in 0..2 {
+            for file in &files {
+                for r#ref in ["base", "head"] {
+                    client
+                        .get_file_content("owner", "repo", &file.filename, r#ref)
+                        .await
+                        .unwrap();
+                }
+            }
+        }
+        diffed.assert_async().await;
+        contents.assert_async().await;
+        listed.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_file_content_falls_back_to_blobs() {
+        let mut server = mockito::Server::new_async().await;
+        let blob_sha = "b".repeat(40);
+        let file = |r#ref: &str| {
+            serde_json::json!({
+                "sha": blob_sha,
+                "filename": "assets/big.json",
+                "status": "added",
+                "additions": 1,
+                "deletions": 0,
+                "changes": 1,
+                "blob_url": null,
+                "raw_url": null,
+                "contents_url": format!("https://api.github.com/repos/owner/repo/contents/assets/big.json?ref={ref}")
+            })
+        };
+        // The commit's files are read from its diff, and listed for their blobs once needed
+        server
+            .mock("GET", "/repos/owner/repo/commits/abc")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_body("diff --git a/assets/big.json b/assets/big.json\nnew file mode 100644\n--- /dev/null\n+++ b/assets/big.json\n@@ -0,0 +1 @@\n+{}\n")
+            .create_async()
+            .await;
+        let listed = server
+            .mock("GET", "/repos/owner/repo/commits/abc")
+            .match_query(mockito::Matcher::Any)
+            .match_header("accept", "application/vnd.github.v3+json")
+            .with_body(serde_json::json!({ "files": [file("abc")] }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        // The pull request's diff isn't rendered, so its files are listed along with their blobs
+        server
+            .mock("GET", "/repos/owner/repo/pulls/1")
+            .match_header("accept", "application/vnd.github.diff")
+            .with_status(406)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/pulls/1/files")
+            .match_query(mockito::Matcher::Any)
+            .with_body(serde_json::json!([file("def")]).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/repos/owner/repo/contents/assets/big.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .with_body(r#"{"message": "Resource not accessible by personal access token"}"#)
+            .create_async()
+            .await;
+        let blob = server
+            .mock(
+                "GET",
+                format!("/repos/owner/repo/git/blobs/{blob_sha}").as_str(),
+            )
+            .with_body("{}")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = mock_client(&server)
+            .await
+            .with_diff_strategy(DiffStrategy::Unified);
+        client
+            .get_commit_files("owner", "repo", "abc")
+            .await
+            .unwrap();
+        client.get_pr_files("owner", "repo", 1).await.unwrap();
+
+        // The same blob at two commits is only fetched once
+        for r#ref in ["abc", "def"] {
+            let content = client
+                .get_file_content("owner", "repo", "assets/big.json", r#ref)
+                .await
+                .unwrap();
+            assert_eq!(content, "{}");
+        }
+        blob.assert_async().await;
+        listed.assert_async().await;
+
+        // Without a known blob, the contents API's error is reported
+        let error = client
+            .get_file_content("owner", "repo", "assets/big.json", "123")
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("not accessible"));
+    }
+
+    #[tokio::test]
+    async fn test_conditional_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let path = "/repos/owner/repo/pulls/1/commits";
+        let changed = server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Any)
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", r#""abc""#)
+            .with_body("[]")
+            .expect(1)
+            .create_async()
+            .await;
+        let unchanged = server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Any)
+            .match_header("if-none-match", r#""abc""#)
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = mock_client(&server).await;
+        for _ in 0..2 {
+            let commits = client.get_pr_commits("owner", "repo", 1).await.unwrap();
+            assert!(commits.is_empty());
+        }
+        changed.assert_async().await;
+        unchanged.assert_async().await;
+    }
+
+    #[test]
+    fn test_truncation_notice() {
+        let mut pr: PullRequest = serde_json::from_value(serde_json::json!({
+            "number": 1,
+            "title": "Rename everything",
+            "body": null,
+            "state": "open",
+            "user": { "login": "octocat", "avatar_url": "" },
+            "created_at": "2024-05-01T10:00:00Z",
+            "updated_at": "2024-05-01T10:00:00Z",
+            "head": { "label": "rename", "ref": "rename", "sha": "head" },
+            "base": { "label": "main", "ref": "main", "sha": "base" },
+            "commits": 12,
+            "additions": 5000,
+            "deletions": 5000,
+            "changed_files": 3400
+        }))
+        .unwrap();
+
+        assert_eq!(
+            pr.truncation_notice(12, 3000).as_deref(),
+            Some("Only 3000 of 3400 files can be listed; see the rest on the web")
+        );
+        pr.commits = 300;
+        assert_eq!(
+            pr.truncation_notice(250, 3000).as_deref(),
+            Some("Only 250 of 300 commits and 3000 of 3400 files can be listed; see the rest on the web")
+        );
+        pr.changed_files = 3000;
+        pr.commits = 250;
+        assert!(pr.truncation_notice(250, 3000).is_none());
+    }
 }
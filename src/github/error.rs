@@ -0,0 +1,123 @@
+use super::rate_limit;
+use reqwest::StatusCode;
+use std::time::Duration;
+use thiserror::Error;
+
+/// What went wrong talking to the GitHub API
+#[derive(Debug, Error)]
+pub enum GitHubError {
+    #[error("Not found on GitHub: {0}")]
+    NotFound(String),
+
+    #[error("GitHub denied access ({status}): {message}. Check that the token is valid and can access the repository")]
+    AuthFailed { status: StatusCode, message: String },
+
+    #[error(
+        "GitHub API rate limit exceeded, try again in {} minutes{}",
+        .retry_after.as_secs().div_ceil(60),
+        if *.authenticated { "" } else { " or provide a token for a higher limit" }
+    )]
+    RateLimited {
+        retry_after: Duration,
+        authenticated: bool,
+    },
+
+    #[error("GitHub rejected the request ({status}): {message}")]
+    Api { status: StatusCode, message: String },
+
+    #[error("GitHub GraphQL error: {0}")]
+    GraphQl(String),
+
+    #[error("Failed to reach GitHub: {0}")]
+    Network(#[from] reqwest::Error),
+
+    #[error("Failed to decode GitHub response: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    #[error("A GitHub token is required for this request")]
+    TokenRequired,
+
+    #[error("Can't send a request with a streamed body")]
+    StreamedBody,
+}
+
+impl GitHubError {
+    /// Whether the request may succeed if it's tried again shortly
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            Self::Api { status, .. } => status.is_server_error(),
+            Self::RateLimited { retry_after, .. } => *retry_after <= rate_limit::MAX_WAIT,
+            _ => false,
+        }
+    }
+
+    /// Whether a request that failed this way may be sent again. Requests that aren't idempotent,
+    /// like posting a comment, are only sent again when GitHub can't have acted on them.
+    pub fn is_retryable(&self, idempotent: bool) -> bool {
+        match self {
+            _ if idempotent => self.is_transient(),
+            Self::Network(e) => e.is_connect(),
+            Self::RateLimited { .. } => self.is_transient(),
+            _ => false,
+        }
+    }
+
+    /// Error for an unsuccessful response, given the `message` GitHub sent with it
+    pub fn from_status(status: StatusCode, url: &str, message: Option<String>) -> Self {
+        let message = message
+            .or_else(|| status.canonical_reason().map(str::to_string))
+            .unwrap_or_default();
+
+        match status {
+            StatusCode::NOT_FOUND => Self::NotFound(url.to_string()),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                Self::AuthFailed { status, message }
+            }
+            _ => Self::Api { status, message },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status() {
+        let url = "https://api.github.com/repos/o/r/pulls/1";
+        assert!(matches!(
+            GitHubError::from_status(StatusCode::NOT_FOUND, url, None),
+            GitHubError::NotFound(_)
+        ));
+
+        let error = GitHubError::from_status(
+            StatusCode::UNAUTHORIZED,
+            url,
+            Some("Bad credentials".to_string()),
+        );
+        assert!(matches!(error, GitHubError::AuthFailed { .. }));
+        assert!(error.to_string().contains("Bad credentials"));
+        assert!(!error.is_transient());
+
+        let error = GitHubError::from_status(StatusCode::BAD_GATEWAY, url, None);
+        assert!(error.is_transient());
+        // A comment may have been posted before the gateway failed
+        assert!(error.is_retryable(true));
+        assert!(!error.is_retryable(false));
+        assert!(error.to_string().contains("Bad Gateway"));
+    }
+
+    #[test]
+    fn test_rate_limited_message() {
+        let error = GitHubError::RateLimited {
+            retry_after: Duration::from_secs(90),
+            authenticated: false,
+        };
+        assert_eq!(
+            error.to_string(),
+            "GitHub API rate limit exceeded, try again in 2 minutes or provide a token for a higher limit"
+        );
+        assert!(!error.is_transient());
+    }
+}
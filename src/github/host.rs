@@ -0,0 +1,171 @@
+use crate::settings::GitHubSettings;
+
+const GITHUB_COM: &str = "github.com";
+
+/// The GitHub instance a pull request lives on: github.com or a GitHub Enterprise Server
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitHubHost {
+    /// Host of the web UI, as found in pull request URLs
+    pub web_host: String,
+    /// Base URL of the REST API, without a trailing slash
+    pub api_url: String,
+}
+
+impl Default for GitHubHost {
+    fn default() -> Self {
+        Self::for_web_host(GITHUB_COM)
+    }
+}
+
+impl GitHubHost {
+    /// Host with the default API location: api.github.com, or `/api/v3` on the server itself
+    pub fn for_web_host(web_host: &str) -> Self {
+        let web_host = web_host.trim_end_matches('/').to_lowercase();
+        let api_url = if web_host == GITHUB_COM {
+            "https://api.github.com".to_string()
+        } else {
+            format!("https://{web_host}/api/v3")
+        };
+        Self { web_host, api_url }
+    }
+
+    /// Pick the host from, in order: CLI flags, the PR URL, the settings file, github.com.
+    /// A configured API URL only applies to the host it was configured for.
+    pub fn resolve(
+        cli_host: Option<&str>,
+        cli_api_url: Option<&str>,
+        url_host: Option<&str>,
+        settings: &GitHubSettings,
+    ) -> Self {
+        let web_host = cli_host
+            .or(url_host)
+            .or(settings.host.as_deref())
+            .unwrap_or(GITHUB_COM);
+        let mut host = Self::for_web_host(web_host);
+
+        let configured_api_url = settings.api_url.as_deref().filter(|_| {
+            settings
+                .host
+                .as_deref()
+                .is_some_and(|h| h.eq_ignore_ascii_case(&host.web_host))
+        });
+        if let Some(api_url) = cli_api_url.or(configured_api_url) {
+            host.api_url = api_url.trim_end_matches('/').to_string();
+        }
+        host
+    }
+
+    pub fn is_github_com(&self) -> bool {
+        self.web_host == GITHUB_COM
+    }
+
+    /// URL of a repository endpoint, e.g. `pulls/1/comments`
+    pub fn repo_url(&self, owner: &str, repo: &str, path: &str) -> String {
+        format!("{}/repos/{owner}/{repo}/{path}", self.api_url)
+    }
+
+    /// URL of a file in the contents API, with the path's segments escaped
+    pub fn contents_url(&self, owner: &str, repo: &str, path: &str) -> String {
+        let base = self.repo_url(owner, repo, "contents");
+        let Ok(mut url) = url::Url::parse(&base) else {
+            return format!("{base}/{path}");
+        };
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.extend(path.split('/'));
+        }
+        url.to_string()
+    }
+
+    pub fn graphql_url(&self) -> String {
+        match self.api_url.strip_suffix("/api/v3") {
+            Some(server) => format!("{server}/api/graphql"),
+            None => format!("{}/graphql", self.api_url),
+        }
+    }
+
+    /// URL serving the raw content of a file at a ref
+    pub fn raw_url(&self, owner: &str, repo: &str, r#ref: &str, path: &str) -> String {
+        if self.is_github_com() {
+            format!("https://raw.githubusercontent.com/{owner}/{repo}/{ref}/{path}")
+        } else {
+            format!("https://{}/raw/{owner}/{repo}/{ref}/{path}", self.web_host)
+        }
+    }
+
+    /// Host name of the API, used as the machine name in ~/.authinfo
+    pub fn api_hostname(&self) -> &str {
+        let without_scheme = self
+            .api_url
+            .split_once("://")
+            .map_or(self.api_url.as_str(), |(_, rest)| rest);
+        without_scheme.split('/').next().unwrap_or(without_scheme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_com_urls() {
+        let host = GitHubHost::default();
+        assert_eq!(host.api_hostname(), "api.github.com");
+        assert_eq!(host.graphql_url(), "https://api.github.com/graphql");
+        assert_eq!(
+            host.repo_url("o", "r", "pulls/1"),
+            "https://api.github.com/repos/o/r/pulls/1"
+        );
+        assert_eq!(
+            host.raw_url("o", "r", "abc", "src/lib.rs"),
+            "https://raw.githubusercontent.com/o/r/abc/src/lib.rs"
+        );
+        assert_eq!(
+            host.contents_url("o", "r", "docs/a b#1.md"),
+            "https://api.github.com/repos/o/r/contents/docs/a%20b%231.md"
+        );
+    }
+
+    #[test]
+    fn test_enterprise_urls() {
+        let host = GitHubHost::for_web_host("GHE.example.com");
+        assert_eq!(host.api_url, "https://ghe.example.com/api/v3");
+        assert_eq!(host.api_hostname(), "ghe.example.com");
+        assert_eq!(host.graphql_url(), "https://ghe.example.com/api/graphql");
+        assert_eq!(
+            host.raw_url("o", "r", "abc", "README.md"),
+            "https://ghe.example.com/raw/o/r/abc/README.md"
+        );
+    }
+
+    #[test]
+    fn test_resolve_precedence() {
+        let settings = GitHubSettings {
+            host: Some("ghe.example.com".to_string()),
+            api_url: Some("https://api.ghe.example.com/".to_string()),
+            ..GitHubSettings::default()
+        };
+
+        // The configured host and API URL are used when nothing else says otherwise
+        let host = GitHubHost::resolve(None, None, None, &settings);
+        assert_eq!(host.web_host, "ghe.example.com");
+        assert_eq!(host.api_url, "https://api.ghe.example.com");
+
+        // A PR URL on another host doesn't pick up the configured API URL
+        let host = GitHubHost::resolve(None, None, Some("github.com"), &settings);
+        assert_eq!(host, GitHubHost::default());
+
+        // CLI flags win over everything
+        let host = GitHubHost::resolve(
+            Some("git.corp.internal"),
+            Some("https://git.corp.internal/custom/api"),
+            Some("github.com"),
+            &settings,
+        );
+        assert_eq!(host.web_host, "git.corp.internal");
+        assert_eq!(host.api_url, "https://git.corp.internal/custom/api");
+        assert_eq!(
+            host.graphql_url(),
+            "https://git.corp.internal/custom/api/graphql"
+        );
+    }
+}
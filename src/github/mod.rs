@@ -1,8 +1,12 @@
 pub mod client;
+pub mod error;
+pub mod host;
 pub mod models;
+pub mod rate_limit;
 
 #[cfg(test)]
 mod client_test;
 
 pub use client::GitHubClient;
+pub use host::GitHubHost;
 pub use models::*;
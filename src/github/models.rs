@@ -1,3 +1,4 @@
+use crate::forge::ForgeKind;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +19,43 @@ pub struct PullRequest {
     pub changed_files: u32,
 }
 
+impl PullRequest {
+    /// Notice for when fewer commits or files were listed than the pull request has,
+    /// as GitHub stops listing them at 250 commits and 3000 files
+    pub fn truncation_notice(&self, commits: usize, files: usize) -> Option<String> {
+        let mut missing = Vec::new();
+        if self.commits as usize > commits {
+            missing.push(format!("{commits} of {} commits", self.commits));
+        }
+        if self.changed_files as usize > files {
+            missing.push(format!("{files} of {} files", self.changed_files));
+        }
+        if missing.is_empty() {
+            return None;
+        }
+
+        Some(format!(
+            "Only {} can be listed; see the rest on the web",
+            missing.join(" and ")
+        ))
+    }
+
+    /// Count the totals a forge didn't report, like GitLab's commits and lines changed,
+    /// from the commits and files that were listed
+    pub fn count_missing_totals(&mut self, commits: usize, files: &[FileChange]) {
+        if self.commits == 0 {
+            self.commits = commits as u32;
+        }
+        if self.additions == 0 && self.deletions == 0 {
+            self.additions = files.iter().map(|f| f.additions).sum();
+            self.deletions = files.iter().map(|f| f.deletions).sum();
+        }
+        if self.changed_files == 0 {
+            self.changed_files = files.len() as u32;
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub login: String,
@@ -39,6 +77,17 @@ pub struct Commit {
     pub committer: Option<User>,
 }
 
+impl Commit {
+    /// Abbreviated SHA for display. Pseudo-commits like `staged` are shown in full.
+    pub fn short_sha(&self) -> &str {
+        if self.sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            self.sha.get(..7).unwrap_or(&self.sha)
+        } else {
+            &self.sha
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitDetail {
     pub message: String,
@@ -53,7 +102,7 @@ pub struct CommitAuthor {
     pub date: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileChange {
     pub filename: String,
     pub status: FileStatus,
@@ -64,7 +113,7 @@ pub struct FileChange {
     pub diff_content: Option<DiffContent>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FileStatus {
     Added,
     Modified,
@@ -73,19 +122,19 @@ pub enum FileStatus {
     Copied,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffContent {
     pub hunks: Vec<DiffHunk>,
     /// Full file content with inline diff annotations
     pub full_file_view: Vec<DiffLine>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffHunk {
     pub lines: Vec<DiffLine>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffLine {
     pub line_type: LineType,
     pub content: String,
@@ -93,7 +142,7 @@ pub struct DiffLine {
     pub new_line_no: Option<usize>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LineType {
     Addition,
     Deletion,
@@ -101,7 +150,531 @@ pub enum LineType {
     Header,
 }
 
+/// Side of the diff a review comment is anchored to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DiffSide {
+    Left,
+    Right,
+}
+
+/// A new pull request review comment anchored to a diff line or range of lines
+#[derive(Debug, Clone, Serialize)]
+pub struct NewReviewComment {
+    pub body: String,
+    pub commit_id: String,
+    pub path: String,
+    /// Last line of the commented range
+    pub line: usize,
+    pub side: DiffSide,
+    /// First line of the range, only set for multi-line comments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_side: Option<DiffSide>,
+}
+
+/// The outcome chosen when submitting a pull request review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewEvent {
+    Comment,
+    Approve,
+    RequestChanges,
+}
+
+impl ReviewEvent {
+    pub const ALL: [ReviewEvent; 3] = [
+        ReviewEvent::Comment,
+        ReviewEvent::Approve,
+        ReviewEvent::RequestChanges,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReviewEvent::Comment => "Comment",
+            ReviewEvent::Approve => "Approve",
+            ReviewEvent::RequestChanges => "Request changes",
+        }
+    }
+
+    /// The next event in selection order, wrapping around
+    pub fn next(self) -> Self {
+        match self {
+            ReviewEvent::Comment => ReviewEvent::Approve,
+            ReviewEvent::Approve => ReviewEvent::RequestChanges,
+            ReviewEvent::RequestChanges => ReviewEvent::Comment,
+        }
+    }
+}
+
+/// A line comment submitted as part of a pull request review
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftReviewComment {
+    pub path: String,
+    pub line: usize,
+    pub side: DiffSide,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_side: Option<DiffSide>,
+    pub body: String,
+}
+
+impl From<&NewReviewComment> for DraftReviewComment {
+    fn from(comment: &NewReviewComment) -> Self {
+        Self {
+            path: comment.path.clone(),
+            line: comment.line,
+            side: comment.side,
+            start_line: comment.start_line,
+            start_side: comment.start_side,
+            body: comment.body.clone(),
+        }
+    }
+}
+
+/// A review comment left on a line of the pull request diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub path: String,
+    pub body: String,
+    pub user: Option<User>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Line in the PR head, or None when the comment is outdated there
+    pub line: Option<usize>,
+    /// Line in the commit the comment was originally made on
+    pub original_line: Option<usize>,
+    pub side: Option<DiffSide>,
+    pub commit_id: String,
+    pub original_commit_id: String,
+    pub in_reply_to_id: Option<u64>,
+    /// The diff hunk ending at the commented line
+    #[serde(default)]
+    pub diff_hunk: String,
+}
+
+/// A root review comment together with its replies, oldest first
+#[derive(Debug, Clone)]
+pub struct ReviewThread {
+    pub comments: Vec<ReviewComment>,
+    /// GraphQL node id, needed to reply to or resolve the thread
+    pub node_id: Option<String>,
+    pub is_resolved: bool,
+}
+
+impl ReviewThread {
+    pub fn new(comments: Vec<ReviewComment>) -> Self {
+        Self {
+            comments,
+            node_id: None,
+            is_resolved: false,
+        }
+    }
+
+    /// Group a flat list of review comments into threads
+    pub fn group(mut comments: Vec<ReviewComment>) -> Vec<ReviewThread> {
+        comments.sort_by_key(|c| c.created_at);
+
+        let mut threads: Vec<ReviewThread> = Vec::new();
+        for comment in comments {
+            // GitHub points every reply at the root comment of its thread
+            let parent = comment
+                .in_reply_to_id
+                .and_then(|id| threads.iter_mut().find(|t| t.root().id == id));
+
+            match parent {
+                Some(thread) => thread.comments.push(comment),
+                None => threads.push(ReviewThread::new(vec![comment])),
+            }
+        }
+
+        threads
+    }
+
+    pub fn root(&self) -> &ReviewComment {
+        &self.comments[0]
+    }
+
+    pub fn path(&self) -> &str {
+        &self.root().path
+    }
+
+    pub fn side(&self) -> DiffSide {
+        self.root().side.unwrap_or(DiffSide::Right)
+    }
+
+    /// Fill in node ids and resolution state, matching threads by their root comment
+    pub fn attach_states(threads: &mut [ReviewThread], states: &[ReviewThreadState]) {
+        for thread in threads {
+            let root_id = thread.root().id;
+            if let Some(state) = states.iter().find(|s| s.root_comment_id() == Some(root_id)) {
+                thread.node_id = Some(state.id.clone());
+                thread.is_resolved = state.is_resolved;
+            }
+        }
+    }
+
+    /// The commented line's content, taken from the end of the diff hunk
+    pub fn anchor_content(&self) -> Option<&str> {
+        let last = self.root().diff_hunk.lines().last()?;
+        if last.starts_with("@@") {
+            return None;
+        }
+        Some(last.get(1..).unwrap_or(""))
+    }
+}
+
+/// Review threads of a pull request, which are shown even when their states can't be loaded
+#[derive(Debug, Clone, Default)]
+pub struct ReviewThreads {
+    pub threads: Vec<ReviewThread>,
+    /// Why the threads are all shown as unresolved, if their states couldn't be loaded
+    pub warning: Option<String>,
+}
+
+/// A review thread as returned by the GraphQL API
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewThreadState {
+    pub id: String,
+    pub is_resolved: bool,
+    comments: ReviewThreadStateComments,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReviewThreadStateComments {
+    nodes: Vec<ReviewThreadStateComment>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadStateComment {
+    database_id: Option<u64>,
+}
+
+impl ReviewThreadState {
+    /// REST id of the thread's first comment
+    pub fn root_comment_id(&self) -> Option<u64> {
+        self.comments.nodes.first()?.database_id
+    }
+}
+
+/// An entry in the pull request conversation
+#[derive(Debug, Clone)]
+pub struct TimelineItem {
+    pub created_at: DateTime<Utc>,
+    /// Login of the user behind the event
+    pub actor: Option<String>,
+    pub kind: TimelineKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineKind {
+    Comment {
+        body: String,
+    },
+    Review {
+        state: String,
+        body: String,
+    },
+    /// Consecutive commits, shown as a single push
+    Push {
+        commits: Vec<String>,
+    },
+    ForcePush {
+        commit_id: Option<String>,
+    },
+    Label {
+        name: String,
+        added: bool,
+    },
+    ReviewRequest {
+        reviewer: String,
+        requested: bool,
+    },
+    /// Any other event, e.g. `closed`, `merged` or `renamed`
+    Other {
+        event: String,
+    },
+}
+
+impl TimelineItem {
+    /// Build conversation entries from raw issue timeline events, in order
+    pub fn from_events(events: &[serde_json::Value]) -> Vec<TimelineItem> {
+        let mut items: Vec<TimelineItem> = Vec::new();
+
+        for event in events {
+            let Some(item) = Self::from_event(event) else {
+                continue;
+            };
+
+            // Fold commits pushed back to back by the same author into one entry
+            if let (
+                TimelineKind::Push { commits },
+                Some(TimelineItem {
+                    kind: TimelineKind::Push { commits: previous },
+                    actor,
+                    ..
+                }),
+            ) = (&item.kind, items.last_mut())
+            {
+                if *actor == item.actor {
+                    previous.extend(commits.iter().cloned());
+                    continue;
+                }
+            }
+
+            items.push(item);
+        }
+
+        items
+    }
+
+    fn from_event(event: &serde_json::Value) -> Option<TimelineItem> {
+        let kind_name = event["event"].as_str()?;
+        let text = |value: &serde_json::Value| value.as_str().unwrap_or_default().to_string();
+
+        let (kind, actor, created_at) = match kind_name {
+            "commented" => (
+                TimelineKind::Comment {
+                    body: text(&event["body"]),
+                },
+                &event["user"]["login"],
+                &event["created_at"],
+            ),
+            "reviewed" => (
+                TimelineKind::Review {
+                    state: text(&event["state"]),
+                    body: text(&event["body"]),
+                },
+                &event["user"]["login"],
+                &event["submitted_at"],
+            ),
+            // Commits carry git metadata instead of a GitHub actor
+            "committed" => (
+                TimelineKind::Push {
+                    commits: vec![text(&event["message"])
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()],
+                },
+                &event["author"]["name"],
+                &event["committer"]["date"],
+            ),
+            "head_ref_force_pushed" => (
+                TimelineKind::ForcePush {
+                    commit_id: event["commit_id"].as_str().map(str::to_string),
+                },
+                &event["actor"]["login"],
+                &event["created_at"],
+            ),
+            "labeled" | "unlabeled" => (
+                TimelineKind::Label {
+                    name: text(&event["label"]["name"]),
+                    added: kind_name == "labeled",
+                },
+                &event["actor"]["login"],
+                &event["created_at"],
+            ),
+            "review_requested" | "review_request_removed" => {
+                let reviewer = event["requested_reviewer"]["login"]
+                    .as_str()
+                    .or_else(|| event["requested_team"]["name"].as_str())
+                    .unwrap_or("someone")
+                    .to_string();
+                (
+                    TimelineKind::ReviewRequest {
+                        reviewer,
+                        requested: kind_name == "review_requested",
+                    },
+                    &event["actor"]["login"],
+                    &event["created_at"],
+                )
+            }
+            // Line comments are shown inline in the diff instead
+            "line-commented" | "commit-commented" => return None,
+            // Notification bookkeeping that GitHub doesn't show in the conversation either
+            "mentioned" | "subscribed" | "unsubscribed" => return None,
+            _ => (
+                TimelineKind::Other {
+                    event: kind_name.to_string(),
+                },
+                &event["actor"]["login"],
+                &event["created_at"],
+            ),
+        };
+
+        Some(TimelineItem {
+            created_at: created_at.as_str()?.parse().ok()?,
+            actor: actor.as_str().map(str::to_string),
+            kind,
+        })
+    }
+}
+
+/// A GitHub Actions (or other app) check run on a commit
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckRun {
+    pub id: u64,
+    pub name: String,
+    /// `queued`, `in_progress` or `completed`
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub output: CheckRunOutput,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckRunOutput {
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub annotations_count: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckRunList {
+    pub total_count: usize,
+    pub check_runs: Vec<CheckRun>,
+}
+
+/// Severity of a check run annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationLevel {
+    Notice,
+    Warning,
+    Failure,
+}
+
+/// A message a check run attached to lines of a file, e.g. a clippy warning
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckAnnotation {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub annotation_level: AnnotationLevel,
+    pub title: Option<String>,
+    pub message: String,
+    /// Name of the check run that reported the annotation
+    #[serde(skip)]
+    pub check_name: String,
+}
+
+/// A status reported through the commit statuses API, e.g. by an external CI
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommitStatus {
+    pub context: String,
+    /// `success`, `failure`, `error` or `pending`
+    pub state: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CombinedStatus {
+    pub statuses: Vec<CommitStatus>,
+}
+
+/// Outcome of a check, ordered from least to most important when combining
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckState {
+    /// Skipped or neutral, doesn't affect the result
+    Neutral,
+    Success,
+    Pending,
+    Failure,
+}
+
+impl CheckState {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CheckState::Neutral => "○",
+            CheckState::Success => "✓",
+            CheckState::Pending => "●",
+            CheckState::Failure => "✗",
+        }
+    }
+}
+
+impl CheckRun {
+    pub fn state(&self) -> CheckState {
+        if self.status != "completed" {
+            return CheckState::Pending;
+        }
+        match self.conclusion.as_deref() {
+            Some("success") => CheckState::Success,
+            Some("neutral" | "skipped" | "stale") => CheckState::Neutral,
+            _ => CheckState::Failure,
+        }
+    }
+}
+
+impl CommitStatus {
+    pub fn state(&self) -> CheckState {
+        match self.state.as_str() {
+            "success" => CheckState::Success,
+            "pending" => CheckState::Pending,
+            _ => CheckState::Failure,
+        }
+    }
+}
+
+/// A check run or commit status, as listed in the checks panel
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckEntry {
+    pub name: String,
+    pub state: CheckState,
+    /// Conclusion or status as reported by GitHub, e.g. `timed_out`
+    pub label: String,
+    pub summary: Option<String>,
+}
+
+/// Check runs and statuses reported for a single commit
+#[derive(Debug, Clone, Default)]
+pub struct CommitChecks {
+    pub check_runs: Vec<CheckRun>,
+    pub statuses: Vec<CommitStatus>,
+}
+
+impl CommitChecks {
+    /// Combined outcome, `None` when nothing reported on the commit
+    pub fn state(&self) -> Option<CheckState> {
+        self.entries().iter().map(|entry| entry.state).max()
+    }
+
+    /// All checks, failures first and then by name
+    pub fn entries(&self) -> Vec<CheckEntry> {
+        let runs = self.check_runs.iter().map(|run| CheckEntry {
+            name: run.name.clone(),
+            state: run.state(),
+            label: run.conclusion.clone().unwrap_or_else(|| run.status.clone()),
+            summary: run
+                .output
+                .title
+                .clone()
+                .or_else(|| run.output.summary.clone())
+                .filter(|s| !s.trim().is_empty()),
+        });
+        let statuses = self.statuses.iter().map(|status| CheckEntry {
+            name: status.context.clone(),
+            state: status.state(),
+            label: status.state.clone(),
+            summary: status.description.clone().filter(|s| !s.trim().is_empty()),
+        });
+
+        let mut entries: Vec<CheckEntry> = runs.chain(statuses).collect();
+        entries.sort_by(|a, b| b.state.cmp(&a.state).then_with(|| a.name.cmp(&b.name)));
+        entries
+    }
+}
+
 pub struct ParsedPrUrl {
+    pub forge: ForgeKind,
+    /// Web host from the URL, `None` when only a PR number was given
+    pub host: Option<String>,
     pub owner: String,
     pub repo: String,
     pub number: u64,
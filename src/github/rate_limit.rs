@@ -0,0 +1,197 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::{header::HeaderMap, StatusCode};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Longest wait for a rate limit to lift before giving up on a request
+pub const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// GitHub's wait for a secondary rate limit that doesn't say how long it lasts
+const SECONDARY_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// API quota as reported by the latest response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset: DateTime<Utc>,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let number = |name: &str| headers.get(name)?.to_str().ok()?.parse::<i64>().ok();
+
+        Some(Self {
+            limit: number("x-ratelimit-limit")? as u32,
+            remaining: number("x-ratelimit-remaining")? as u32,
+            reset: Utc
+                .timestamp_opt(number("x-ratelimit-reset")?, 0)
+                .single()?,
+        })
+    }
+
+    /// Time until the quota is reset
+    fn until_reset(&self) -> Duration {
+        (self.reset - Utc::now()).to_std().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    quota: Option<RateLimit>,
+    /// Set by a secondary rate limit, which applies on top of the quota
+    paused_until: Option<Instant>,
+}
+
+/// Keeps track of GitHub's rate limits across requests, shared by clones of the client
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    state: Arc<Mutex<State>>,
+}
+
+impl RateLimiter {
+    pub fn quota(&self) -> Option<RateLimit> {
+        self.state.lock().unwrap().quota
+    }
+
+    /// Record the rate limit headers of a response.
+    /// Returns how long to wait before retrying if the request was rate limited.
+    pub fn observe(&self, status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+        let quota = RateLimit::from_headers(headers);
+        let mut state = self.state.lock().unwrap();
+        if quota.is_some() {
+            state.quota = quota;
+        }
+
+        if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+
+        let retry_after = headers
+            .get("retry-after")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs);
+        let delay = match (retry_after, quota) {
+            (Some(delay), _) => delay,
+            // The reset time only has a precision of seconds
+            (None, Some(quota)) if quota.remaining == 0 => {
+                quota.until_reset() + Duration::from_secs(1)
+            }
+            (None, _) if status == StatusCode::TOO_MANY_REQUESTS => SECONDARY_LIMIT_WAIT,
+            // A 403 without rate limit headers is about permissions
+            (None, _) => return None,
+        };
+
+        if delay <= MAX_WAIT {
+            state.paused_until = Some(Instant::now() + delay);
+        }
+        Some(delay)
+    }
+
+    /// Wait until requests may be sent again, when it's worth waiting for.
+    /// Requests are held back while a secondary rate limit is in effect or the quota is
+    /// used up, as long as it's reset within [`MAX_WAIT`].
+    pub async fn wait(&self) {
+        let until = {
+            let state = self.state.lock().unwrap();
+            let quota_reset = state
+                .quota
+                .filter(|quota| quota.remaining == 0)
+                .map(|quota| quota.until_reset())
+                .filter(|delay| *delay <= MAX_WAIT)
+                .map(|delay| Instant::now() + delay);
+            state.paused_until.max(quota_reset)
+        };
+
+        if let Some(until) = until {
+            tokio::time::sleep_until(until).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn quota_headers(remaining: u32, reset_in: i64) -> HeaderMap {
+        let reset = Utc::now().timestamp() + reset_in;
+        headers(&[
+            ("x-ratelimit-limit", "5000".to_string()),
+            ("x-ratelimit-remaining", remaining.to_string()),
+            ("x-ratelimit-reset", reset.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_observe_quota() {
+        let limiter = RateLimiter::default();
+        assert!(limiter.quota().is_none());
+
+        assert_eq!(
+            limiter.observe(StatusCode::OK, &quota_headers(4321, 600)),
+            None
+        );
+        let quota = limiter.quota().unwrap();
+        assert_eq!((quota.limit, quota.remaining), (5000, 4321));
+
+        // Responses without the headers, like raw file contents, keep the last known quota
+        assert_eq!(limiter.observe(StatusCode::OK, &HeaderMap::new()), None);
+        assert_eq!(limiter.quota(), Some(quota));
+
+        // A 403 that isn't about rate limits isn't retried
+        assert_eq!(
+            limiter.observe(StatusCode::FORBIDDEN, &quota_headers(4000, 600)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_observe_rate_limited() {
+        let limiter = RateLimiter::default();
+
+        let secondary = headers(&[("retry-after", "5".to_string())]);
+        assert_eq!(
+            limiter.observe(StatusCode::FORBIDDEN, &secondary),
+            Some(Duration::from_secs(5))
+        );
+
+        let delay = limiter
+            .observe(StatusCode::FORBIDDEN, &quota_headers(0, 1800))
+            .unwrap();
+        assert!(delay > MAX_WAIT);
+        assert_eq!(limiter.quota().unwrap().remaining, 0);
+
+        assert_eq!(
+            limiter.observe(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new()),
+            Some(SECONDARY_LIMIT_WAIT)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_secondary_limit() {
+        let limiter = RateLimiter::default();
+        let secondary = headers(&[("retry-after", "5".to_string())]);
+        limiter.observe(StatusCode::TOO_MANY_REQUESTS, &secondary);
+
+        let start = Instant::now();
+        limiter.wait().await;
+        assert!(start.elapsed() >= Duration::from_secs(5));
+
+        // The pause is over, so later requests go through right away
+        let start = Instant::now();
+        limiter.wait().await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::settings::HttpSettings;
+use anyhow::{Context, Result};
+use reqwest::{Certificate, Client, Proxy};
+use std::{fs, time::Duration};
+
+/// Build the HTTP client shared by all requests to a forge, so that connections are reused
+pub fn client(settings: &HttpSettings) -> Result<Client> {
+    let mut builder = Client::builder()
+        .user_agent(&settings.user_agent)
+        .connect_timeout(Duration::from_secs(settings.connect_timeout))
+        .timeout(Duration::from_secs(settings.timeout));
+
+    // Without a proxy in the settings, the HTTPS_PROXY and ALL_PROXY variables are used
+    if let Some(ref proxy) = settings.proxy {
+        let proxy = Proxy::all(proxy).with_context(|| format!("Invalid proxy URL {proxy}"))?;
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(ref path) = settings.ca_bundle {
+        let pem = fs::read(path)
+            .with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+        let certificates = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid CA bundle {}", path.display()))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    builder.build().context("Failed to create HTTP client")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_settings() {
+        assert!(client(&HttpSettings::default()).is_ok());
+
+        let settings = HttpSettings {
+            proxy: Some("not a proxy url".to_string()),
+            ..HttpSettings::default()
+        };
+        assert!(client(&settings).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("ca.pem");
+        fs::write(
+            &bundle,
+            "-----BEGIN CERTIFICATE-----\nnot base64\n-----END CERTIFICATE-----\n",
+        )
+        .unwrap();
+        let settings = HttpSettings {
+            ca_bundle: Some(bundle),
+            ..HttpSettings::default()
+        };
+        let error = client(&settings).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid CA bundle"));
+    }
+}
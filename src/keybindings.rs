@@ -44,6 +44,26 @@ pub struct KeyBindings {
     pub next_match: Vec<String>,
     #[serde(default = "default_prev_match")]
     pub prev_match: Vec<String>,
+    #[serde(default = "default_add_comment")]
+    pub add_comment: Vec<String>,
+    #[serde(default = "default_submit_review")]
+    pub submit_review: Vec<String>,
+    #[serde(default = "default_toggle_overview")]
+    pub toggle_overview: Vec<String>,
+    #[serde(default = "default_toggle_conversation")]
+    pub toggle_conversation: Vec<String>,
+    #[serde(default = "default_toggle_checks")]
+    pub toggle_checks: Vec<String>,
+    #[serde(default = "default_toggle_selection")]
+    pub toggle_selection: Vec<String>,
+    #[serde(default = "default_suggest_change")]
+    pub suggest_change: Vec<String>,
+    #[serde(default = "default_reply_thread")]
+    pub reply_thread: Vec<String>,
+    #[serde(default = "default_toggle_resolved")]
+    pub toggle_resolved: Vec<String>,
+    #[serde(default = "default_toggle_thread")]
+    pub toggle_thread: Vec<String>,
 }
 
 // Default key bindings - Vim-style with alternatives
@@ -123,6 +143,46 @@ fn default_prev_match() -> Vec<String> {
     vec!["N".to_string(), "Shift+n".to_string()]
 }
 
+fn default_add_comment() -> Vec<String> {
+    vec!["c".to_string()]
+}
+
+fn default_submit_review() -> Vec<String> {
+    vec!["S".to_string()]
+}
+
+fn default_toggle_overview() -> Vec<String> {
+    vec!["i".to_string()]
+}
+
+fn default_toggle_conversation() -> Vec<String> {
+    vec!["C".to_string()]
+}
+
+fn default_toggle_checks() -> Vec<String> {
+    vec!["K".to_string()]
+}
+
+fn default_toggle_selection() -> Vec<String> {
+    vec!["v".to_string()]
+}
+
+fn default_suggest_change() -> Vec<String> {
+    vec!["s".to_string()]
+}
+
+fn default_reply_thread() -> Vec<String> {
+    vec!["R".to_string()]
+}
+
+fn default_toggle_resolved() -> Vec<String> {
+    vec!["x".to_string()]
+}
+
+fn default_toggle_thread() -> Vec<String> {
+    vec!["e".to_string()]
+}
+
 impl Default for KeyBindings {
     fn default() -> Self {
         Self {
@@ -145,6 +205,16 @@ impl Default for KeyBindings {
             start_search: default_start_search(),
             next_match: default_next_match(),
             prev_match: default_prev_match(),
+            add_comment: default_add_comment(),
+            submit_review: default_submit_review(),
+            toggle_overview: default_toggle_overview(),
+            toggle_conversation: default_toggle_conversation(),
+            toggle_checks: default_toggle_checks(),
+            toggle_selection: default_toggle_selection(),
+            suggest_change: default_suggest_change(),
+            reply_thread: default_reply_thread(),
+            toggle_resolved: default_toggle_resolved(),
+            toggle_thread: default_toggle_thread(),
         }
     }
 }
@@ -184,6 +254,16 @@ impl KeyBindings {
         add_mappings(&self.start_search, Action::StartSearch)?;
         add_mappings(&self.next_match, Action::NextMatch)?;
         add_mappings(&self.prev_match, Action::PrevMatch)?;
+        add_mappings(&self.add_comment, Action::AddComment)?;
+        add_mappings(&self.submit_review, Action::SubmitReview)?;
+        add_mappings(&self.toggle_overview, Action::ToggleOverview)?;
+        add_mappings(&self.toggle_conversation, Action::ToggleConversation)?;
+        add_mappings(&self.toggle_checks, Action::ToggleChecks)?;
+        add_mappings(&self.toggle_selection, Action::ToggleSelection)?;
+        add_mappings(&self.suggest_change, Action::SuggestChange)?;
+        add_mappings(&self.reply_thread, Action::ReplyThread)?;
+        add_mappings(&self.toggle_resolved, Action::ToggleResolved)?;
+        add_mappings(&self.toggle_thread, Action::ToggleThread)?;
 
         Ok(map)
     }
@@ -279,6 +359,15 @@ impl KeyBindings {
             quit: self.quit.first().cloned().unwrap_or_default(),
             next_hunk: self.next_hunk.first().cloned().unwrap_or_default(),
             prev_hunk: self.prev_hunk.first().cloned().unwrap_or_default(),
+            add_comment: self.add_comment.first().cloned().unwrap_or_default(),
+            submit_review: self.submit_review.first().cloned().unwrap_or_default(),
+            toggle_overview: self.toggle_overview.first().cloned().unwrap_or_default(),
+            toggle_conversation: self
+                .toggle_conversation
+                .first()
+                .cloned()
+                .unwrap_or_default(),
+            toggle_checks: self.toggle_checks.first().cloned().unwrap_or_default(),
         }
     }
 }
@@ -295,6 +384,11 @@ pub struct KeyDisplays {
     pub quit: String,
     pub next_hunk: String,
     pub prev_hunk: String,
+    pub add_comment: String,
+    pub submit_review: String,
+    pub toggle_overview: String,
+    pub toggle_conversation: String,
+    pub toggle_checks: String,
 }
 
 #[cfg(test)]
@@ -366,5 +460,32 @@ mod tests {
 
         let k = KeyEvent::new(KeyCode::Char('k'), KeyModifiers::empty());
         assert_eq!(mapping.get(&k).cloned(), Some(Action::NavigateUp));
+
+        let c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::empty());
+        assert_eq!(mapping.get(&c).cloned(), Some(Action::AddComment));
+
+        let shift_s = KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT);
+        assert_eq!(mapping.get(&shift_s).cloned(), Some(Action::SubmitReview));
+
+        let shift_r = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT);
+        assert_eq!(mapping.get(&shift_r).cloned(), Some(Action::ReplyThread));
+
+        let i = KeyEvent::new(KeyCode::Char('i'), KeyModifiers::empty());
+        assert_eq!(mapping.get(&i).cloned(), Some(Action::ToggleOverview));
+
+        let shift_c = KeyEvent::new(KeyCode::Char('C'), KeyModifiers::SHIFT);
+        assert_eq!(
+            mapping.get(&shift_c).cloned(),
+            Some(Action::ToggleConversation)
+        );
+
+        let shift_k = KeyEvent::new(KeyCode::Char('K'), KeyModifiers::SHIFT);
+        assert_eq!(mapping.get(&shift_k).cloned(), Some(Action::ToggleChecks));
+
+        let s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::empty());
+        assert_eq!(mapping.get(&s).cloned(), Some(Action::SuggestChange));
+
+        let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::empty());
+        assert_eq!(mapping.get(&x).cloned(), Some(Action::ToggleResolved));
     }
 }
@@ -3,7 +3,9 @@ mod auth;
 mod cache;
 mod diff;
 mod events;
+mod forge;
 mod github;
+mod http;
 mod keybindings;
 mod settings;
 mod syntax_highlight;
@@ -11,24 +13,41 @@ mod theme;
 mod ui;
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{io, time::Duration};
+use std::{
+    fs::OpenOptions,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use crate::{
-    app::{App, AppState, InputMode, LoadingStatus, LoadingStepStatus},
+    app::{App, AppState, InputMode, LoadingStatus, LoadingStepStatus, Screen, StatusMessage},
+    cache::{DiskCache, FileContentCache, ResponseCache},
     diff::DiffParser,
     events::{Action, Event, EventHandler},
-    github::{Commit, FileChange, GitHubClient, PullRequest},
+    forge::{Bundle, Forge, ForgeKind, GitLabClient, GiteaClient, LocalRepo, Patch, WorkingTree},
+    github::{
+        Commit, FileChange, GitHubClient, GitHubHost, ParsedPrUrl, PullRequest, ReviewThreads,
+    },
+    settings::{DiffStrategy, Settings},
     ui::{AppLayout, Navigation, Sidebar},
 };
 
-type PRData = (PullRequest, Vec<Commit>, Vec<FileChange>);
+struct PRData {
+    pr: PullRequest,
+    commits: Vec<Commit>,
+    files: Vec<FileChange>,
+    /// Loading review comments may fail without failing the whole load
+    review_threads: Result<ReviewThreads>,
+}
 
 enum LoadingUpdate {
     Status(LoadingStatus),
@@ -37,13 +56,38 @@ enum LoadingUpdate {
 
 #[derive(Parser, Debug)]
 #[command(name = "revu")]
-#[command(about = "TUI application for reviewing GitHub PRs", long_about = None)]
+#[command(about = "TUI application for reviewing GitHub PRs, GitLab MRs and local branches", long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
-    /// GitHub PR URL or PR number (e.g., https://github.com/owner/repo/pull/123 or 123)
-    pr: String,
-
-    /// GitHub personal access token (can also be set via GITHUB_TOKEN env var)
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// GitHub PR URL, GitLab MR URL, Gitea/Forgejo PR URL or PR number, or - to read a diff from stdin
+    /// (e.g., https://github.com/owner/repo/pull/123, https://gitlab.com/group/project/-/merge_requests/45 or 123)
+    #[arg(
+        required_unless_present_any = ["local", "working_tree", "patch", "bundle"],
+        conflicts_with_all = ["local", "working_tree", "patch", "bundle"]
+    )]
+    pr: Option<String>,
+
+    /// Review a range of the local git repository instead, e.g. main..feature
+    #[arg(long, value_name = "BASE..HEAD", conflicts_with = "working_tree")]
+    local: Option<String>,
+
+    /// Review the uncommitted changes of the local git repository, staged and unstaged
     #[arg(short, long)]
+    working_tree: bool,
+
+    /// Review a unified diff read from a file, e.g. the output of `git diff`
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["local", "working_tree"])]
+    patch: Option<PathBuf>,
+
+    /// Review a pull request exported with `revu export`, without network access
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["local", "working_tree", "patch"])]
+    bundle: Option<PathBuf>,
+
+    /// Personal access token (can also be set via GITHUB_TOKEN, GITLAB_TOKEN or GITEA_TOKEN env var)
+    #[arg(short, long, global = true)]
     token: Option<String>,
 
     /// Repository owner (required if using PR number instead of URL)
@@ -53,6 +97,35 @@ struct Cli {
     /// Repository name (required if using PR number instead of URL)
     #[arg(short, long)]
     repo: Option<String>,
+
+    /// GitHub host, e.g. github.example.com for GitHub Enterprise Server, or GitLab host
+    /// (defaults to the PR URL's host, then the config file, then github.com)
+    #[arg(long, global = true)]
+    host: Option<String>,
+
+    /// API base URL (defaults to https://api.github.com or https://HOST/api/v3 for GitHub,
+    /// https://HOST/api/v4 for GitLab, https://HOST/api/v1 for Gitea)
+    #[arg(long, global = true)]
+    api_url: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare two local files or directories, e.g. from `git difftool`
+    Diff {
+        /// The old file or directory
+        old: PathBuf,
+        /// The new file or directory
+        new: PathBuf,
+    },
+    /// Save a pull request to a file, to review it offline with --bundle
+    Export {
+        /// PR or MR URL, or a PR number with GITHUB_OWNER and GITHUB_REPO set
+        pr: String,
+        /// File to write the bundle to
+        #[arg(short, long, value_name = "FILE")]
+        output: PathBuf,
+    },
 }
 
 #[tokio::main]
@@ -68,48 +141,94 @@ async fn main() -> Result<()> {
     // Parse CLI arguments
     let cli = Cli::parse();
 
-    // Get token using priority ordering: CLI -> authinfo -> env var
-    let token = auth::get_github_token(cli.token).context("Failed to get GitHub token")?;
-
-    if token.is_none() {
-        eprintln!("Warning: No GitHub token found. You may encounter rate limits.");
-        eprintln!("Please provide authentication using one of these methods:");
-        eprintln!("  1. Command line: --token YOUR_TOKEN");
-        eprintln!("  2. ~/.authinfo file: machine api.github.com login USERNAME password TOKEN");
-        eprintln!("  3. Environment variable: export GITHUB_TOKEN=YOUR_TOKEN");
-    }
-
     // Set owner/repo env vars if provided via CLI
-    if let Some(owner) = cli.owner {
+    if let Some(ref owner) = cli.owner {
         std::env::set_var("GITHUB_OWNER", owner);
     }
-    if let Some(repo) = cli.repo {
+    if let Some(ref repo) = cli.repo {
         std::env::set_var("GITHUB_REPO", repo);
     }
 
-    // Create application
-    let mut app = App::new(&cli.pr, token)
-        .await
-        .context("Failed to initialize application")?;
+    let settings = Settings::load().unwrap_or_default();
+    let disk_cache = DiskCache::open(&settings.cache);
 
-    // Check if we're in a TTY environment
-    if !crossterm::tty::IsTty::is_tty(&io::stdout()) {
-        eprintln!("Error: This application requires a terminal environment to run.");
+    if let Some(Command::Export { ref pr, ref output }) = cli.command {
+        let parsed = forge::parse_review_url(pr)?;
+        let (forge, _) = connect(&cli, &parsed, &settings, disk_cache).await?;
         eprintln!(
-            "Please run this command directly in a terminal, not through a pipe or redirect."
+            "Exporting {}/{}#{}...",
+            parsed.owner, parsed.repo, parsed.number
         );
-        eprintln!("\nNote: PR data would be fetched for: {}", cli.pr);
-        eprintln!("To test, run: cargo run -- {}", cli.pr);
+        Bundle::export(forge.as_ref(), &parsed)
+            .await?
+            .save(output)?;
+        eprintln!("Saved to {}", output.display());
         return Ok(());
     }
+    // `revu -` reads a diff from stdin, like a pager
+    let patch = cli
+        .patch
+        .clone()
+        .or_else(|| cli.pr.as_deref().filter(|pr| *pr == "-").map(PathBuf::from));
+
+    // Local ranges and the working tree are read with git, and bundles, patches and local files
+    // as they are, without any forge or token
+    let mut banner = None;
+    let (parsed, forge, github) = if let Some(Command::Diff { ref old, ref new }) = cli.command {
+        let comparison = Patch::compare(old, new)?;
+        (
+            comparison.parsed_url(),
+            Arc::new(comparison) as Arc<dyn Forge>,
+            None,
+        )
+    } else if let Some(ref path) = cli.bundle {
+        let bundle = Bundle::open(path)?;
+        banner = Some(bundle.snapshot_notice());
+        (
+            bundle.parsed_url(),
+            Arc::new(bundle) as Arc<dyn Forge>,
+            None,
+        )
+    } else if let Some(ref path) = patch {
+        let patch = Patch::read(path)?;
+        (patch.parsed_url(), Arc::new(patch) as Arc<dyn Forge>, None)
+    } else if let Some(ref range) = cli.local {
+        let repo = LocalRepo::open(Path::new("."), range).await?;
+        (repo.parsed_url(), Arc::new(repo) as Arc<dyn Forge>, None)
+    } else if cli.working_tree {
+        let tree = WorkingTree::open(Path::new(".")).await?;
+        (tree.parsed_url(), Arc::new(tree) as Arc<dyn Forge>, None)
+    } else {
+        let parsed = forge::parse_review_url(cli.pr.as_deref().unwrap_or_default())?;
+        let (forge, github) = connect(&cli, &parsed, &settings, disk_cache.clone()).await?;
+        (parsed, forge, github)
+    };
+    // Create application
+    let mut app = App::new(parsed, forge, github, settings, disk_cache)
+        .context("Failed to initialize application")?;
+    app.banner = banner;
+
+    // Draw on the controlling terminal when stdout is piped or redirected.
+    // Keys are read from it too, so stdin may have carried a patch.
+    let mut output: Box<dyn Write> = if io::stdout().is_terminal() {
+        Box::new(io::stdout())
+    } else {
+        match OpenOptions::new().write(true).open("/dev/tty") {
+            Ok(tty) => Box::new(tty),
+            Err(_) => {
+                eprintln!("Error: This application requires a terminal environment to run.");
+                eprintln!("Please run this command from a terminal.");
+                return Ok(());
+            }
+        }
+    };
 
     // Setup terminal
     enable_raw_mode()
         .context("Failed to enable raw mode - make sure you're running in a terminal")?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
+    execute!(output, EnterAlternateScreen, EnableMouseCapture)
         .context("Failed to setup terminal")?;
-    let backend = CrosstermBackend::new(stdout);
+    let backend = CrosstermBackend::new(output);
     let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
 
     // Clear terminal
@@ -131,6 +250,87 @@ async fn main() -> Result<()> {
     app_result
 }
 
+/// Create the client for the forge the pull request lives on.
+/// GitHub pull requests also get a GitHub client for comments, reviews and CI.
+async fn connect(
+    cli: &Cli,
+    parsed: &ParsedPrUrl,
+    settings: &Settings,
+    disk_cache: Option<DiskCache>,
+) -> Result<(Arc<dyn Forge>, Option<GitHubClient>)> {
+    let http = http::client(&settings.http)?;
+    let cache = FileContentCache::persistent(100, disk_cache.clone());
+
+    match parsed.forge {
+        ForgeKind::GitHub => {
+            // Work out which GitHub instance the PR lives on
+            let host = GitHubHost::resolve(
+                cli.host.as_deref(),
+                cli.api_url.as_deref(),
+                parsed.host.as_deref(),
+                &settings.github,
+            );
+            let token = find_token(cli, host.api_hostname(), "GITHUB_TOKEN")?;
+            let responses = ResponseCache::new(500, disk_cache);
+            let client = GitHubClient::new(token, host, http, cache, responses)
+                .await?
+                .with_diff_strategy(settings.github.diff_strategy);
+            Ok((Arc::new(client.clone()), Some(client)))
+        }
+        ForgeKind::GitLab => {
+            let host = cli
+                .host
+                .clone()
+                .or_else(|| parsed.host.clone())
+                .unwrap_or_else(|| "gitlab.com".to_string());
+            let api_url = cli
+                .api_url
+                .clone()
+                .unwrap_or_else(|| GitLabClient::api_url_for_host(&host));
+            let token = find_token(cli, &host, "GITLAB_TOKEN")?;
+            Ok((
+                Arc::new(GitLabClient::new(token, &api_url, http, cache)?),
+                None,
+            ))
+        }
+        ForgeKind::Gitea => {
+            let host = cli
+                .host
+                .clone()
+                .or_else(|| parsed.host.clone())
+                .context("A host is required for Gitea pull requests")?;
+            let api_url = cli
+                .api_url
+                .clone()
+                .unwrap_or_else(|| GiteaClient::api_url_for_host(&host));
+            let token = find_token(cli, &host, "GITEA_TOKEN")?;
+            Ok((
+                Arc::new(GiteaClient::new(token, &api_url, http, cache)?),
+                None,
+            ))
+        }
+        ForgeKind::Local | ForgeKind::Patch | ForgeKind::Bundle => {
+            anyhow::bail!("Local ranges, patches and bundles are read without a forge")
+        }
+    }
+}
+
+/// Get the API token using priority ordering: CLI -> authinfo -> env var
+fn find_token(cli: &Cli, machine: &str, env_var: &str) -> Result<Option<String>> {
+    let token =
+        auth::get_token(cli.token.clone(), machine, env_var).context("Failed to get API token")?;
+
+    if token.is_none() {
+        eprintln!("Warning: No API token found. You may encounter rate limits.");
+        eprintln!("Please provide authentication using one of these methods:");
+        eprintln!("  1. Command line: --token YOUR_TOKEN");
+        eprintln!("  2. ~/.authinfo file: machine {machine} login USERNAME password TOKEN");
+        eprintln!("  3. Environment variable: export {env_var}=YOUR_TOKEN");
+    }
+
+    Ok(token)
+}
+
 async fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
@@ -149,10 +349,11 @@ async fn run_app<B: ratatui::backend::Backend>(
     let owner = app.owner.clone();
     let repo = app.repo.clone();
     let pr_number = app.pr_number;
-    let client = app.client.clone();
+    let forge = Arc::clone(&app.forge);
+    let github = app.github.clone();
 
     tokio::spawn(async move {
-        let result = load_pr_data_async(client, owner, repo, pr_number, tx.clone()).await;
+        let result = load_pr_data_async(forge, github, owner, repo, pr_number, tx.clone()).await;
         let _ = tx.send(LoadingUpdate::Complete(Box::new(result))).await;
     });
 
@@ -168,11 +369,28 @@ async fn run_app<B: ratatui::backend::Backend>(
                     }
                     LoadingUpdate::Complete(result) => {
                         match *result {
-                            Ok((pr, commits, files)) => {
+                            Ok(PRData {
+                                pr,
+                                commits,
+                                files,
+                                review_threads,
+                            }) => {
+                                app.banner = pr
+                                    .truncation_notice(commits.len(), files.len())
+                                    .or(app.banner.take());
                                 app.pr = Some(pr);
                                 app.commits = commits.clone();
                                 app.navigation = Some(Navigation::new(commits));
 
+                                match review_threads {
+                                    Ok(threads) => app.set_review_threads(threads),
+                                    Err(e) => {
+                                        app.status_message = Some(StatusMessage::error(format!(
+                                            "Failed to load review comments: {e:#}"
+                                        )));
+                                    }
+                                }
+
                                 // Load files for first commit
                                 if !app.commits.is_empty() {
                                     if let Ok(()) = app.load_commit_files(0).await {
@@ -180,11 +398,7 @@ async fn run_app<B: ratatui::backend::Backend>(
                                         if !app.files.is_empty() {
                                             let _ = app.load_file_diff(0).await;
                                             // Update diff view with the loaded content
-                                            if let Some(ref sidebar) = app.sidebar {
-                                                if let Some(file) = sidebar.get_selected_file() {
-                                                    app.diff_view.set_file(Some(file.clone()));
-                                                }
-                                            }
+                                            app.show_selected_file();
                                         }
                                         app.state = AppState::Ready;
                                     } else {
@@ -198,6 +412,9 @@ async fn run_app<B: ratatui::backend::Backend>(
                                     app.state = AppState::Ready;
                                 }
 
+                                // CI results only decorate the commits, so they're loaded once
+                                // the review is shown
+                                app.reload_checks();
                                 data_loaded = true;
                             }
                             Err(e) => {
@@ -210,8 +427,11 @@ async fn run_app<B: ratatui::backend::Backend>(
             }
         }
 
-        // Set cursor visibility based on search mode
-        if app.input_mode == InputMode::Search {
+        // Set cursor visibility based on text input modes
+        if matches!(
+            app.input_mode,
+            InputMode::Search | InputMode::Comment | InputMode::Review
+        ) {
             terminal.show_cursor()?;
         } else {
             terminal.hide_cursor()?;
@@ -229,35 +449,100 @@ async fn run_app<B: ratatui::backend::Backend>(
                     AppLayout::render_error(f, size, error, &app.theme);
                 }
                 AppState::Ready => {
-                    let (sidebar_area, diff_area, nav_area) = AppLayout::split_main(size);
-
-                    // Render sidebar with focus state
-                    if let Some(ref mut sidebar) = app.sidebar {
-                        let is_focused = matches!(app.focused_pane, app::FocusedPane::Sidebar);
-                        sidebar.render(f, sidebar_area, &app.theme, is_focused);
+                    let mut main_area = size;
+                    if let Some(ref banner) = app.banner {
+                        let (banner_area, rest) = AppLayout::split_banner(size);
+                        AppLayout::render_banner(f, banner_area, banner, &app.theme);
+                        main_area = rest;
                     }
+                    let (sidebar_area, diff_area, nav_area) = AppLayout::split_main(main_area);
+
+                    match app.screen {
+                        Screen::Checks => {
+                            let commit = app
+                                .navigation
+                                .as_ref()
+                                .and_then(|nav| nav.get_current_commit());
+                            let checks = commit.and_then(|c| app.commit_checks.get(&c.sha));
+                            app.checks_view.render(
+                                f,
+                                sidebar_area.union(diff_area),
+                                &app.theme,
+                                commit,
+                                checks,
+                            );
+                        }
+                        Screen::Conversation => {
+                            app.conversation.render(
+                                f,
+                                sidebar_area.union(diff_area),
+                                &app.theme,
+                                &app.settings.keybindings.get_display_keys().add_comment,
+                            );
+                        }
+                        Screen::Overview => {
+                            // The overview takes up the whole content area
+                            if let Some(ref pr) = app.pr {
+                                app.overview.render(
+                                    f,
+                                    sidebar_area.union(diff_area),
+                                    &app.theme,
+                                    pr,
+                                );
+                            }
+                        }
+                        Screen::Diff => {
+                            // Render sidebar with focus state
+                            if let Some(ref mut sidebar) = app.sidebar {
+                                let is_focused =
+                                    matches!(app.focused_pane, app::FocusedPane::Sidebar);
+                                sidebar.render(f, sidebar_area, &app.theme, is_focused);
+                            }
 
-                    // Render diff view with focus state
-                    let is_diff_focused = matches!(app.focused_pane, app::FocusedPane::DiffView);
-                    app.diff_view
-                        .render(f, diff_area, &app.theme, is_diff_focused);
+                            // Render diff view with focus state
+                            let is_diff_focused =
+                                matches!(app.focused_pane, app::FocusedPane::DiffView);
+                            app.diff_view
+                                .render(f, diff_area, &app.theme, is_diff_focused);
+                        }
+                    }
 
                     // Render navigation with current focus
-                    if let Some(ref navigation) = app.navigation {
+                    if let Some(ref mut navigation) = app.navigation {
+                        navigation.rate_limit =
+                            app.github.as_ref().and_then(GitHubClient::rate_limit);
                         navigation.render(
                             f,
                             nav_area,
                             &app.theme,
                             app.focused_pane,
                             &app.settings.keybindings,
+                            app.status_message.as_ref(),
                         );
                     }
+
+                    // Render the comment editor on top of the diff view or the current screen
+                    if app.input_mode == InputMode::Comment {
+                        let area = match app.screen {
+                            Screen::Diff => diff_area,
+                            _ => sidebar_area.union(diff_area),
+                        };
+                        app.comment_input.render(f, area, &app.theme);
+                    }
+
+                    // Render the review dialog over the whole screen
+                    if app.input_mode == InputMode::Review {
+                        app.review_dialog
+                            .render(f, size, &app.theme, &app.draft_comments);
+                    }
                 }
             }
         })?;
 
         // No longer need loading animation frame
 
+        app.poll_checks().await;
+
         // Handle events
         if let Some(event) = event_handler.poll(Duration::from_millis(100))? {
             match event {
@@ -283,12 +568,78 @@ async fn run_app<B: ratatui::backend::Backend>(
                             }
                             _ => {}
                         }
+                    } else if app.input_mode == InputMode::Comment {
+                        use crossterm::event::{KeyCode, KeyModifiers};
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.cancel_comment();
+                            }
+                            KeyCode::Char('s') if key.modifiers == KeyModifiers::CONTROL => {
+                                app.submit_comment().await;
+                            }
+                            KeyCode::Char('d') if key.modifiers == KeyModifiers::CONTROL => {
+                                app.add_draft_comment();
+                            }
+                            KeyCode::Enter => {
+                                app.comment_input.insert_newline();
+                            }
+                            KeyCode::Backspace => {
+                                app.comment_input.backspace();
+                            }
+                            KeyCode::Left => {
+                                app.comment_input.move_left();
+                            }
+                            KeyCode::Right => {
+                                app.comment_input.move_right();
+                            }
+                            KeyCode::Char(c) if key.modifiers != KeyModifiers::CONTROL => {
+                                app.comment_input.insert_char(c);
+                            }
+                            _ => {}
+                        }
+                    } else if app.input_mode == InputMode::Review {
+                        use crossterm::event::{KeyCode, KeyModifiers};
+                        match key.code {
+                            KeyCode::Esc => {
+                                app.cancel_review();
+                            }
+                            KeyCode::Tab => {
+                                app.review_dialog.cycle_event();
+                            }
+                            KeyCode::Char('s') if key.modifiers == KeyModifiers::CONTROL => {
+                                app.submit_review().await;
+                            }
+                            KeyCode::Enter => {
+                                app.review_dialog.body.insert_newline();
+                            }
+                            KeyCode::Backspace => {
+                                app.review_dialog.body.backspace();
+                            }
+                            KeyCode::Left => {
+                                app.review_dialog.body.move_left();
+                            }
+                            KeyCode::Right => {
+                                app.review_dialog.body.move_right();
+                            }
+                            KeyCode::Char(c) if key.modifiers != KeyModifiers::CONTROL => {
+                                app.review_dialog.body.insert_char(c);
+                            }
+                            _ => {}
+                        }
                     } else if let Some(action) = Action::from_key_event(key, &key_mapping) {
+                        // Any new action dismisses the previous status message
+                        app.status_message = None;
+
                         // Normal mode actions
                         match action {
+                            _ if app.screen != Screen::Diff => {
+                                app.handle_screen_action(action).await?;
+                            }
                             Action::Quit => {
-                                // Check if search is active first
-                                if app.diff_view.search_mode || app.diff_view.search_active {
+                                // Check if search or a line selection is active first
+                                if app.diff_view.selection_anchor.is_some() {
+                                    app.diff_view.clear_selection();
+                                } else if app.diff_view.search_mode || app.diff_view.search_active {
                                     // Clear search instead of quitting
                                     app.diff_view.clear_search();
                                     app.input_mode = InputMode::Normal;
@@ -356,6 +707,36 @@ async fn run_app<B: ratatui::backend::Backend>(
                                 app.diff_view.exit_search();
                                 app.input_mode = InputMode::Normal;
                             }
+                            Action::AddComment => {
+                                app.start_comment();
+                            }
+                            Action::SubmitReview => {
+                                app.start_review();
+                            }
+                            Action::ToggleOverview => {
+                                app.toggle_screen(Screen::Overview).await;
+                            }
+                            Action::ToggleConversation => {
+                                app.toggle_screen(Screen::Conversation).await;
+                            }
+                            Action::ToggleChecks => {
+                                app.toggle_screen(Screen::Checks).await;
+                            }
+                            Action::ToggleSelection => {
+                                app.diff_view.toggle_selection();
+                            }
+                            Action::SuggestChange => {
+                                app.start_suggestion();
+                            }
+                            Action::ReplyThread => {
+                                app.start_reply();
+                            }
+                            Action::ToggleResolved => {
+                                app.toggle_thread_resolved().await;
+                            }
+                            Action::ToggleThread => {
+                                app.diff_view.toggle_thread_expanded();
+                            }
                             _ => {}
                         }
                     }
@@ -366,12 +747,18 @@ async fn run_app<B: ratatui::backend::Backend>(
                     match mouse.kind {
                         MouseEventKind::ScrollDown => {
                             if matches!(app.state, AppState::Ready) {
-                                app.diff_view.scroll_down(3);
+                                match app.screen_scroll() {
+                                    Some(scroll) => scroll.scroll_down(3),
+                                    None => app.diff_view.scroll_down(3),
+                                }
                             }
                         }
                         MouseEventKind::ScrollUp => {
                             if matches!(app.state, AppState::Ready) {
-                                app.diff_view.scroll_up(3);
+                                match app.screen_scroll() {
+                                    Some(scroll) => scroll.scroll_up(3),
+                                    None => app.diff_view.scroll_up(3),
+                                }
                             }
                         }
                         MouseEventKind::Down(_button) => {
@@ -399,7 +786,8 @@ async fn run_app<B: ratatui::backend::Backend>(
 }
 
 async fn load_pr_data_async(
-    client: GitHubClient,
+    forge: Arc<dyn Forge>,
+    github: Option<GitHubClient>,
     owner: String,
     repo: String,
     pr_number: u64,
@@ -412,7 +800,7 @@ async fn load_pr_data_async(
     loading_status.set_current_message("Fetching pull request details...".to_string());
     let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
 
-    let pr = client.get_pull_request(&owner, &repo, pr_number).await?;
+    let mut pr = forge.get_pull_request(&owner, &repo, pr_number).await?;
 
     loading_status.update_step(1, LoadingStepStatus::Completed);
     let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
@@ -422,7 +810,7 @@ async fn load_pr_data_async(
     loading_status.set_current_message("Fetching commits...".to_string());
     let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
 
-    let commits = client.get_pr_commits(&owner, &repo, pr_number).await?;
+    let commits = forge.get_pr_commits(&owner, &repo, pr_number).await?;
     let commit_count = commits.len();
 
     loading_status.update_step(2, LoadingStepStatus::Completed);
@@ -434,7 +822,8 @@ async fn load_pr_data_async(
     loading_status.set_current_message("Fetching file changes...".to_string());
     let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
 
-    let mut files = client.get_pr_files(&owner, &repo, pr_number).await?;
+    let mut files = forge.get_pr_files(&owner, &repo, pr_number).await?;
+    pr.count_missing_totals(commit_count, &files);
 
     loading_status.update_step(3, LoadingStepStatus::Completed);
     let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
@@ -444,18 +833,47 @@ async fn load_pr_data_async(
     loading_status.set_current_message("Processing diffs...".to_string());
     let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
 
-    DiffParser::enrich_file_changes(
-        &mut files,
-        &client,
-        &owner,
-        &repo,
-        &pr.base.sha,
-        &pr.head.sha,
-    )
-    .await?;
+    // Contents from the API count against the rate limit, so they're only fetched as files
+    // are opened
+    let strategy = github.as_ref().map(GitHubClient::diff_strategy);
+    if strategy.is_none_or(|strategy| strategy == DiffStrategy::Raw) {
+        DiffParser::enrich_file_changes(
+            &mut files,
+            &forge,
+            &owner,
+            &repo,
+            &pr.base.sha,
+            &pr.head.sha,
+        )
+        .await?;
+    }
 
     loading_status.update_step(4, LoadingStepStatus::Completed);
     let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
 
-    Ok((pr, commits, files))
+    // Load existing review comments
+    loading_status.update_step(5, LoadingStepStatus::InProgress);
+    loading_status.set_current_message("Fetching review comments...".to_string());
+    let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
+
+    let review_threads = match github {
+        Some(ref github) => github.get_review_threads(&owner, &repo, pr_number).await,
+        None => Ok(ReviewThreads::default()),
+    };
+
+    loading_status.update_step(5, LoadingStepStatus::Completed);
+    if let Ok(ref threads) = review_threads {
+        loading_status.steps[5].name = format!(
+            "Loading review comments ({} threads found)",
+            threads.threads.len()
+        );
+    }
+    let _ = tx.send(LoadingUpdate::Status(loading_status.clone())).await;
+
+    Ok(PRData {
+        pr,
+        commits,
+        files,
+        review_threads,
+    })
 }
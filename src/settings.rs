@@ -14,9 +14,116 @@ pub struct Settings {
     #[serde(default)]
     pub vim_mode: bool,
     #[serde(default)]
+    pub github: GitHubSettings,
+    #[serde(default)]
+    pub http: HttpSettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    #[serde(default)]
     pub keybindings: KeyBindings,
 }
 
+/// Where to find a GitHub Enterprise Server instance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitHubSettings {
+    /// Web host used when it can't be taken from the PR URL, e.g. `github.example.com`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// REST API base URL for `host`, defaults to `https://{host}/api/v3`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    #[serde(default)]
+    pub diff_strategy: DiffStrategy,
+}
+
+/// How the files and diffs of GitHub pull requests are loaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStrategy {
+    /// File lists with their patches from the API, and the full files from the raw host
+    #[default]
+    Raw,
+    /// One unified diff per commit, and the full files from the contents API as they're opened
+    Unified,
+    /// One unified diff per commit, showing only its hunks
+    Hunks,
+}
+
+/// How requests to forges are sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpSettings {
+    /// Proxy for all requests, e.g. `http://proxy.example.com:3128`.
+    /// The `HTTPS_PROXY` and `ALL_PROXY` environment variables are used when it's not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// PEM file of CA certificates to trust on top of the system's, e.g. a corporate CA
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+    /// Seconds to wait for a connection to be made
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout: u64,
+    /// Seconds to wait for a whole request, until its response has been read
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    #[serde(default = "default_user_agent")]
+    pub user_agent: String,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            ca_bundle: None,
+            connect_timeout: default_connect_timeout(),
+            timeout: default_timeout(),
+            user_agent: default_user_agent(),
+        }
+    }
+}
+
+/// Cache of file contents and diffs kept between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Directory of the cache, defaults to `$XDG_CACHE_HOME/revu`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    /// Size in megabytes above which the least recently used entries are removed
+    #[serde(default = "default_cache_size")]
+    pub max_size_mb: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+            max_size_mb: default_cache_size(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_cache_size() -> u64 {
+    500
+}
+
+fn default_connect_timeout() -> u64 {
+    10
+}
+
+fn default_timeout() -> u64 {
+    60
+}
+
+fn default_user_agent() -> String {
+    format!("revu/{}", env!("CARGO_PKG_VERSION"))
+}
+
 fn default_theme() -> String {
     "catppuccin-mocha".to_string()
 }
@@ -31,6 +138,9 @@ impl Default for Settings {
             theme: default_theme(),
             show_line_numbers: default_show_line_numbers(),
             vim_mode: false,
+            github: GitHubSettings::default(),
+            http: HttpSettings::default(),
+            cache: CacheSettings::default(),
             keybindings: KeyBindings::default(),
         }
     }
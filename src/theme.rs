@@ -332,6 +332,19 @@ impl Theme {
     }
 }
 
+#[cfg(test)]
+impl Theme {
+    /// The default theme, parsed from the embedded file without touching the config dir
+    pub fn for_tests() -> Self {
+        let colors = toml::from_str(include_str!("../themes/catppuccin-mocha.toml"))
+            .expect("Embedded theme should parse");
+        Self {
+            name: "catppuccin-mocha".to_string(),
+            colors,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,189 @@
+use crate::{
+    github::models::{AnnotationLevel, CheckAnnotation, DiffLine},
+    theme::Theme,
+    ui::comment_thread::GUTTER_WIDTH,
+};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Check run annotations positioned in the full file view
+#[derive(Debug, Clone, Default)]
+pub struct PlacedAnnotations {
+    /// Annotations keyed by the line their message is shown below
+    pub by_line: BTreeMap<usize, Vec<CheckAnnotation>>,
+    /// Most severe level touching each annotated line, for the gutter marker
+    pub markers: HashMap<usize, AnnotationLevel>,
+}
+
+/// Attach annotations to the lines of the new file they cover.
+/// Messages go below the last covered line, annotations on lines missing from the view are dropped.
+pub fn place_annotations(
+    annotations: Vec<CheckAnnotation>,
+    lines: &[DiffLine],
+) -> PlacedAnnotations {
+    let mut placed = PlacedAnnotations::default();
+
+    for annotation in annotations {
+        let covered: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| {
+                l.new_line_no
+                    .is_some_and(|n| (annotation.start_line..=annotation.end_line).contains(&n))
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        let Some(&last) = covered.last() else {
+            continue;
+        };
+
+        for idx in covered {
+            let marker = placed
+                .markers
+                .entry(idx)
+                .or_insert(annotation.annotation_level);
+            *marker = (*marker).max(annotation.annotation_level);
+        }
+        placed.by_line.entry(last).or_default().push(annotation);
+    }
+
+    placed
+}
+
+pub fn level_color(level: AnnotationLevel, theme: &Theme) -> Color {
+    match level {
+        AnnotationLevel::Failure => theme.error(),
+        AnnotationLevel::Warning => theme.warning(),
+        AnnotationLevel::Notice => theme.info(),
+    }
+}
+
+fn level_label(level: AnnotationLevel) -> &'static str {
+    match level {
+        AnnotationLevel::Failure => "error",
+        AnnotationLevel::Warning => "warning",
+        AnnotationLevel::Notice => "notice",
+    }
+}
+
+/// Lay out an annotation as plain text rows, indented to line up with the code.
+/// Collapsed annotations show a single summary row.
+pub fn annotation_rows(annotation: &CheckAnnotation, expanded: bool) -> Vec<String> {
+    let indent = " ".repeat(GUTTER_WIDTH);
+    let mut message = annotation.message.lines();
+    let summary = annotation
+        .title
+        .clone()
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| message.next().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut rows = vec![format!(
+        "{indent}{} {} [{}] {summary}",
+        if expanded { "▾" } else { "▸" },
+        level_label(annotation.annotation_level),
+        annotation.check_name,
+    )];
+    if expanded {
+        // Skip the line already used as the summary when there's no title
+        let body = if annotation
+            .title
+            .as_deref()
+            .is_some_and(|t| !t.trim().is_empty())
+        {
+            annotation.message.lines()
+        } else {
+            message
+        };
+        rows.extend(body.map(|line| format!("{indent}  {}", line.replace('\t', "    "))));
+    }
+    rows
+}
+
+/// Style an annotation's rows for display
+pub fn render_annotation(
+    annotation: &CheckAnnotation,
+    expanded: bool,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let color = level_color(annotation.annotation_level, theme);
+    annotation_rows(annotation, expanded)
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let style = if i == 0 {
+                Style::default().fg(color).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+            Line::from(Span::styled(row, style))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::models::LineType;
+
+    fn line(content: &str, old: Option<usize>, new: Option<usize>) -> DiffLine {
+        DiffLine {
+            line_type: LineType::Context,
+            content: content.to_string(),
+            old_line_no: old,
+            new_line_no: new,
+        }
+    }
+
+    fn annotation(start: usize, end: usize, level: AnnotationLevel) -> CheckAnnotation {
+        CheckAnnotation {
+            path: "src/lib.rs".to_string(),
+            start_line: start,
+            end_line: end,
+            annotation_level: level,
+            title: None,
+            message: "unused variable: `x`\nhelp: prefix it with an underscore".to_string(),
+            check_name: "clippy".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_place_annotations() {
+        let lines = vec![
+            line("fn main() {", Some(1), Some(1)),
+            line("    let x = 1;", None, Some(2)),
+            line("    old();", Some(2), None),
+            line("}", Some(3), Some(3)),
+        ];
+        let placed = place_annotations(
+            vec![
+                annotation(2, 3, AnnotationLevel::Warning),
+                annotation(3, 3, AnnotationLevel::Failure),
+                annotation(40, 40, AnnotationLevel::Failure),
+            ],
+            &lines,
+        );
+
+        // Both messages go below line 3, the one past the end of the file is dropped
+        assert_eq!(placed.by_line.keys().collect::<Vec<_>>(), [&3]);
+        assert_eq!(placed.by_line[&3].len(), 2);
+        assert_eq!(placed.markers[&1], AnnotationLevel::Warning);
+        assert_eq!(placed.markers[&3], AnnotationLevel::Failure);
+        assert!(!placed.markers.contains_key(&2));
+    }
+
+    #[test]
+    fn test_annotation_rows() {
+        let annotation = annotation(2, 2, AnnotationLevel::Warning);
+        let rows = annotation_rows(&annotation, false);
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].ends_with("▸ warning [clippy] unused variable: `x`"));
+
+        let rows = annotation_rows(&annotation, true);
+        assert_eq!(rows.len(), 2);
+        assert!(rows[1].ends_with("  help: prefix it with an underscore"));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    github::models::{CheckEntry, CheckState, Commit, CommitChecks},
+    theme::Theme,
+    ui::scroll::ScrollState,
+};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Summaries can be whole reports, only the start is shown in the panel
+const MAX_SUMMARY_LINES: usize = 4;
+
+/// Screen listing the CI checks reported on the selected commit
+pub struct ChecksView {
+    pub scroll: ScrollState,
+}
+
+impl ChecksView {
+    pub fn new() -> Self {
+        Self {
+            scroll: ScrollState::new(),
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        commit: Option<&Commit>,
+        checks: Option<&CommitChecks>,
+    ) {
+        let entries = checks.map(CommitChecks::entries).unwrap_or_default();
+        let lines = match checks {
+            Some(_) if entries.is_empty() => vec![placeholder(
+                "No checks were reported for this commit.",
+                theme,
+            )],
+            Some(_) => check_lines(&entries, theme),
+            None => vec![placeholder(
+                "Checks haven't been loaded for this commit.",
+                theme,
+            )],
+        };
+        self.scroll.fit(&lines, area);
+
+        let short_sha = commit.map(Commit::short_sha).unwrap_or("HEAD");
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(format!(" Checks for {short_sha} {}", counts(&entries)))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_focused()))
+                    .style(Style::default().bg(theme.bg()).fg(theme.fg())),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll.offset, 0));
+        f.render_widget(paragraph, area);
+        self.scroll.render_scrollbar(f, area, theme);
+    }
+}
+
+pub fn state_color(state: CheckState, theme: &Theme) -> Color {
+    match state {
+        CheckState::Success => theme.success(),
+        CheckState::Failure => theme.error(),
+        CheckState::Pending => theme.warning(),
+        CheckState::Neutral => theme.context(),
+    }
+}
+
+/// Totals for the panel title, e.g. "(1 failing, 4 passed) "
+fn counts(entries: &[CheckEntry]) -> String {
+    let count = |state| entries.iter().filter(|e| e.state == state).count();
+    let parts: Vec<String> = [
+        (CheckState::Failure, "failing"),
+        (CheckState::Pending, "pending"),
+        (CheckState::Success, "passed"),
+        (CheckState::Neutral, "skipped"),
+    ]
+    .into_iter()
+    .filter_map(|(state, label)| match count(state) {
+        0 => None,
+        n => Some(format!("{n} {label}")),
+    })
+    .collect();
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("({}) ", parts.join(", "))
+    }
+}
+
+fn placeholder(text: &'static str, theme: &Theme) -> Line<'static> {
+    Line::from(Span::styled(
+        text,
+        Style::default()
+            .fg(theme.context())
+            .add_modifier(Modifier::ITALIC),
+    ))
+}
+
+/// One line per check with its conclusion, followed by the start of its summary
+fn check_lines(entries: &[CheckEntry], theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for entry in entries {
+        let color = state_color(entry.state, theme);
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", entry.state.symbol()),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                entry.name.clone(),
+                Style::default().fg(theme.fg()).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {}", entry.label.replace('_', " ")),
+                Style::default().fg(color),
+            ),
+        ]));
+
+        if let Some(ref summary) = entry.summary {
+            for line in summary
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .take(MAX_SUMMARY_LINES)
+            {
+                lines.push(Line::from(vec![
+                    Span::styled("  │ ", Style::default().fg(theme.border())),
+                    Span::styled(line.to_string(), Style::default().fg(theme.subtitle())),
+                ]));
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, state: CheckState, summary: Option<&str>) -> CheckEntry {
+        CheckEntry {
+            name: name.to_string(),
+            state,
+            label: "x".to_string(),
+            summary: summary.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_counts_and_summaries() {
+        let entries = vec![
+            entry(
+                "clippy",
+                CheckState::Failure,
+                Some("2 errors\n\na\nb\nc\nd"),
+            ),
+            entry("build", CheckState::Success, None),
+            entry("docs", CheckState::Success, None),
+        ];
+        assert_eq!(counts(&entries), "(1 failing, 2 passed) ");
+        assert_eq!(counts(&[]), "");
+
+        // Header rows for each check, summaries capped and without blank lines
+        let lines = check_lines(&entries, &Theme::for_tests());
+        assert_eq!(lines.len(), 3 + MAX_SUMMARY_LINES);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{
+    diff::DiffParser,
+    github::models::{DiffSide, LineType},
+    theme::Theme,
+    ui::layout::centered_rect,
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+const SUGGESTION_FENCE: &str = "```suggestion";
+
+/// The diff line or range of lines a comment is being written for
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommentTarget {
+    pub path: String,
+    /// Last line of the range
+    pub line: usize,
+    pub side: DiffSide,
+    /// First line of the range, for multi-line comments
+    pub start_line: Option<usize>,
+    pub start_side: Option<DiffSide>,
+    pub commit_id: String,
+    /// GraphQL id of the review thread when writing a reply
+    pub reply_to: Option<String>,
+}
+
+impl CommentTarget {
+    /// `path:line`, or `path:start-end` for a range
+    pub fn location(&self) -> String {
+        match self.start_line {
+            Some(start) => format!("{}:{}-{}", self.path, start, self.line),
+            None => format!("{}:{}", self.path, self.line),
+        }
+    }
+}
+
+/// Multi-line text input used to compose review comments
+pub struct CommentInput {
+    pub text: String,
+    /// Byte offset of the cursor within `text`
+    pub cursor: usize,
+    pub target: Option<CommentTarget>,
+    /// Original content of the suggested lines, set when suggesting a change
+    pub suggestion_base: Option<String>,
+}
+
+impl CommentInput {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            cursor: 0,
+            target: None,
+            suggestion_base: None,
+        }
+    }
+
+    pub fn start(&mut self, target: CommentTarget) {
+        self.text.clear();
+        self.cursor = 0;
+        self.target = Some(target);
+        self.suggestion_base = None;
+    }
+
+    /// Start a comment pre-filled with a suggestion block holding the original lines
+    pub fn start_suggestion(&mut self, target: CommentTarget, original: String) {
+        self.text = format!("{SUGGESTION_FENCE}\n{original}\n```");
+        // Leave the cursor at the end of the suggested code
+        self.cursor = SUGGESTION_FENCE.len() + 1 + original.len();
+        self.target = Some(target);
+        self.suggestion_base = Some(original);
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+        self.target = None;
+        self.suggestion_base = None;
+    }
+
+    /// The code inside the suggestion block, if the text has one
+    pub fn suggested_code(&self) -> Option<&str> {
+        let start = self.text.find(SUGGESTION_FENCE)? + SUGGESTION_FENCE.len();
+        let rest = self.text[start..].strip_prefix('\n')?;
+
+        // An empty suggestion deletes the lines, so the closing fence may follow directly
+        if let Some(rest) = rest.strip_prefix("```") {
+            return rest.trim().is_empty().then_some("");
+        }
+        let end = rest.find("\n```")?;
+        Some(&rest[..end])
+    }
+
+    pub fn insert_char(&mut self, ch: char) {
+        self.text.insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+    }
+
+    pub fn insert_newline(&mut self) {
+        self.insert_char('\n');
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(ch) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+            self.text.remove(self.cursor);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(ch) = self.text[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(ch) = self.text[self.cursor..].chars().next() {
+            self.cursor += ch.len_utf8();
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    /// Row and column (in characters) of the cursor within the text
+    pub fn cursor_position(&self) -> (u16, u16) {
+        let before = &self.text[..self.cursor];
+        let row = before.matches('\n').count();
+        let col = before
+            .rsplit('\n')
+            .next()
+            .map(|l| l.chars().count())
+            .unwrap_or(0);
+        (row as u16, col as u16)
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        let title = match self.target {
+            Some(ref target) if target.reply_to.is_some() => format!(
+                " Reply on {} (Ctrl+s: post, Esc: cancel) ",
+                target.location()
+            ),
+            Some(ref target) if self.suggestion_base.is_some() => format!(
+                " Suggest change on {} (Ctrl+s: post, Ctrl+d: add to review, Esc: cancel) ",
+                target.location()
+            ),
+            Some(ref target) => format!(
+                " Comment on {} (Ctrl+s: post, Ctrl+d: add to review, Esc: cancel) ",
+                target.location()
+            ),
+            None => " Comment on the pull request (Ctrl+s: post, Esc: cancel) ".to_string(),
+        };
+
+        // Suggestions get a taller popup with the preview below the editor
+        let (popup_area, preview_area) = match self.suggestion_base {
+            Some(_) => {
+                let popup = centered_rect(70, 70, area);
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(popup);
+                (chunks[0], Some(chunks[1]))
+            }
+            None => (centered_rect(70, 40, area), None),
+        };
+        f.render_widget(Clear, popup_area);
+
+        let lines: Vec<Line> = self.text.split('\n').map(Line::from).collect();
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_focused()))
+                    .style(Style::default().bg(theme.bg()).fg(theme.fg())),
+            )
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(paragraph, popup_area);
+
+        if let Some(preview_area) = preview_area {
+            self.render_suggestion_preview(f, preview_area, theme);
+        }
+
+        // Show cursor inside the popup border
+        let (row, col) = self.cursor_position();
+        f.set_cursor_position((
+            popup_area.x + 1 + col.min(popup_area.width.saturating_sub(3)),
+            popup_area.y + 1 + row.min(popup_area.height.saturating_sub(3)),
+        ));
+    }
+
+    /// Show the diff the suggestion would apply to the selected lines
+    fn render_suggestion_preview(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        f.render_widget(Clear, area);
+
+        let base = self.suggestion_base.as_deref().unwrap_or("");
+        let lines: Vec<Line> = match self.suggested_code() {
+            Some(suggested) => match DiffParser::create_full_file_diff(base, suggested, "") {
+                Ok(diff) => diff
+                    .full_file_view
+                    .into_iter()
+                    .map(|line| {
+                        let (prefix, color) = match line.line_type {
+                            LineType::Addition => ("+", theme.added()),
+                            LineType::Deletion => ("-", theme.removed()),
+                            LineType::Context | LineType::Header => (" ", theme.context()),
+                        };
+                        Line::from(Span::styled(
+                            format!("{prefix} {}", line.content),
+                            Style::default().fg(color),
+                        ))
+                    })
+                    .collect(),
+                Err(e) => vec![Line::from(Span::styled(
+                    format!("Failed to build preview: {e}"),
+                    Style::default().fg(theme.error()),
+                ))],
+            },
+            None => vec![Line::from(Span::styled(
+                "No complete ```suggestion block to preview",
+                Style::default().fg(theme.warning()),
+            ))],
+        };
+
+        let preview = Paragraph::new(lines).block(
+            Block::default()
+                .title(" Preview ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border()))
+                .style(Style::default().bg(theme.bg()).fg(theme.fg())),
+        );
+        f.render_widget(preview, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing_multibyte_text() {
+        let mut input = CommentInput::new();
+        input.insert_char('a');
+        input.insert_char('é');
+        input.insert_newline();
+        input.insert_char('b');
+        assert_eq!(input.text, "aé\nb");
+        assert_eq!(input.cursor_position(), (1, 1));
+
+        input.move_left();
+        input.move_left();
+        input.backspace();
+        assert_eq!(input.text, "a\nb");
+        assert_eq!(input.cursor_position(), (0, 1));
+
+        input.move_right();
+        input.backspace();
+        assert_eq!(input.text, "ab");
+    }
+
+    #[test]
+    fn test_empty_input_detection() {
+        let mut input = CommentInput::new();
+        assert!(input.is_empty());
+        input.insert_char(' ');
+        input.insert_newline();
+        assert!(input.is_empty());
+        input.insert_char('x');
+        assert!(!input.is_empty());
+    }
+
+    #[test]
+    fn test_suggestion_prefill_and_extraction() {
+        let target = CommentTarget {
+            path: "src/lib.rs".to_string(),
+            line: 12,
+            side: DiffSide::Right,
+            start_line: Some(11),
+            start_side: Some(DiffSide::Right),
+            commit_id: "abc".to_string(),
+            reply_to: None,
+        };
+        assert_eq!(target.location(), "src/lib.rs:11-12");
+
+        let mut input = CommentInput::new();
+        input.start_suggestion(target, "let a = 1;\nlet b = 2;".to_string());
+        assert_eq!(input.text, "```suggestion\nlet a = 1;\nlet b = 2;\n```");
+        assert_eq!(input.suggested_code(), Some("let a = 1;\nlet b = 2;"));
+
+        // Typing lands at the end of the suggested code
+        input.insert_char('!');
+        assert_eq!(input.suggested_code(), Some("let a = 1;\nlet b = 2;!"));
+
+        // Emptying the block suggests deleting the lines
+        input.text = "Drop these\n```suggestion\n```".to_string();
+        assert_eq!(input.suggested_code(), Some(""));
+
+        input.text = "```suggestion\nunterminated".to_string();
+        assert_eq!(input.suggested_code(), None);
+    }
+}
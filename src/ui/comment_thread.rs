@@ -0,0 +1,283 @@
+use crate::{
+    github::models::{DiffLine, DiffSide, ReviewThread},
+    theme::Theme,
+};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use std::collections::BTreeMap;
+
+/// Width of the line number gutter and diff prefix in the full file view
+pub const GUTTER_WIDTH: usize = 14;
+
+/// A review thread positioned below a line of the full file view
+#[derive(Debug, Clone)]
+pub struct PlacedThread {
+    pub thread: ReviewThread,
+    /// The commented line no longer exists in the diff being shown
+    pub outdated: bool,
+    /// Shown as a single summary row instead of the full conversation
+    pub collapsed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThreadRowKind {
+    Collapsed,
+    Header,
+    Body,
+    Footer,
+}
+
+/// A single rendered row of a review thread
+#[derive(Debug, Clone)]
+pub struct ThreadRow {
+    pub kind: ThreadRowKind,
+    pub text: String,
+}
+
+/// Attach threads to lines of the full file view, keyed by line index.
+/// Threads whose anchor can't be found are shown after the last line and marked outdated.
+pub fn place_threads(
+    threads: Vec<ReviewThread>,
+    lines: &[DiffLine],
+) -> BTreeMap<usize, Vec<PlacedThread>> {
+    let mut placed: BTreeMap<usize, Vec<PlacedThread>> = BTreeMap::new();
+    if lines.is_empty() {
+        return placed;
+    }
+
+    for thread in threads {
+        let (line_idx, outdated) = match find_anchor(&thread, lines) {
+            Some(idx) => (idx, false),
+            None => (lines.len() - 1, true),
+        };
+        let collapsed = thread.is_resolved;
+        placed.entry(line_idx).or_default().push(PlacedThread {
+            thread,
+            outdated,
+            collapsed,
+        });
+    }
+
+    placed
+}
+
+/// Find the line a thread belongs to, trying its current line before its original one.
+/// A candidate only counts if its content still matches the commented line.
+fn find_anchor(thread: &ReviewThread, lines: &[DiffLine]) -> Option<usize> {
+    let root = thread.root();
+    let expected = thread.anchor_content().map(str::trim_end);
+
+    [root.line, root.original_line]
+        .into_iter()
+        .flatten()
+        .find_map(|line_no| {
+            let idx = lines.iter().position(|l| match thread.side() {
+                DiffSide::Left => l.old_line_no == Some(line_no),
+                DiffSide::Right => l.new_line_no == Some(line_no),
+            })?;
+
+            match expected {
+                Some(content) if lines[idx].content.trim_end() != content => None,
+                _ => Some(idx),
+            }
+        })
+}
+
+/// Lay out a thread as plain text rows, indented to line up with the code
+pub fn thread_rows(placed: &PlacedThread) -> Vec<ThreadRow> {
+    let indent = " ".repeat(GUTTER_WIDTH);
+    let mut rows = Vec::new();
+
+    if placed.collapsed {
+        let root = placed.thread.root();
+        let author = root
+            .user
+            .as_ref()
+            .map(|u| u.login.as_str())
+            .unwrap_or("ghost");
+        let count = placed.thread.comments.len();
+        rows.push(ThreadRow {
+            kind: ThreadRowKind::Collapsed,
+            text: format!(
+                "{indent}▸ Resolved thread by {author} ({count} comment{})",
+                if count == 1 { "" } else { "s" }
+            ),
+        });
+        return rows;
+    }
+
+    for (i, comment) in placed.thread.comments.iter().enumerate() {
+        let corner = if i == 0 { "┌─" } else { "├─" };
+        let author = comment
+            .user
+            .as_ref()
+            .map(|u| u.login.as_str())
+            .unwrap_or("ghost");
+        let mut header = format!(
+            "{indent}{corner} {author} · {}",
+            comment.created_at.format("%Y-%m-%d %H:%M")
+        );
+        if i == 0 && placed.thread.is_resolved {
+            header.push_str("  [resolved]");
+        }
+        if i == 0 && placed.outdated {
+            header.push_str("  [outdated]");
+        }
+        rows.push(ThreadRow {
+            kind: ThreadRowKind::Header,
+            text: header,
+        });
+
+        for line in comment.body.lines() {
+            rows.push(ThreadRow {
+                kind: ThreadRowKind::Body,
+                text: format!("{indent}│ {}", line.replace('\t', "    ")),
+            });
+        }
+    }
+
+    rows.push(ThreadRow {
+        kind: ThreadRowKind::Footer,
+        text: format!("{indent}└─"),
+    });
+
+    rows
+}
+
+/// Style a thread's rows for display
+pub fn render_thread(placed: &PlacedThread, theme: &Theme) -> Vec<Line<'static>> {
+    thread_rows(placed)
+        .into_iter()
+        .map(|row| {
+            let style = match row.kind {
+                ThreadRowKind::Collapsed => Style::default()
+                    .fg(theme.context())
+                    .add_modifier(Modifier::ITALIC),
+                ThreadRowKind::Header if placed.outdated => Style::default()
+                    .fg(theme.warning())
+                    .add_modifier(Modifier::BOLD),
+                ThreadRowKind::Header => Style::default()
+                    .fg(theme.info())
+                    .add_modifier(Modifier::BOLD),
+                ThreadRowKind::Body => Style::default().fg(theme.fg()),
+                ThreadRowKind::Footer => Style::default().fg(theme.border()),
+            };
+            Line::from(Span::styled(row.text, style))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::models::{LineType, ReviewComment};
+    use chrono::Utc;
+
+    fn line(
+        line_type: LineType,
+        content: &str,
+        old: Option<usize>,
+        new: Option<usize>,
+    ) -> DiffLine {
+        DiffLine {
+            line_type,
+            content: content.to_string(),
+            old_line_no: old,
+            new_line_no: new,
+        }
+    }
+
+    fn comment(line: Option<usize>, original_line: Option<usize>, hunk: &str) -> ReviewComment {
+        ReviewComment {
+            id: 1,
+            path: "src/lib.rs".to_string(),
+            body: "Why?\nPlease explain".to_string(),
+            user: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            line,
+            original_line,
+            side: Some(DiffSide::Right),
+            commit_id: "head".to_string(),
+            original_commit_id: "orig".to_string(),
+            in_reply_to_id: None,
+            diff_hunk: hunk.to_string(),
+        }
+    }
+
+    fn lines() -> Vec<DiffLine> {
+        vec![
+            line(LineType::Context, "fn main() {", Some(1), Some(1)),
+            line(LineType::Deletion, "    old();", Some(2), None),
+            line(LineType::Addition, "    new();", None, Some(2)),
+            line(LineType::Context, "}", Some(3), Some(3)),
+        ]
+    }
+
+    #[test]
+    fn test_thread_placed_on_matching_line() {
+        let thread = ReviewThread::new(vec![comment(
+            Some(2),
+            Some(2),
+            "@@ -1,3 +1,3 @@\n+    new();",
+        )]);
+
+        let placed = place_threads(vec![thread], &lines());
+        let at_line = &placed[&2];
+        assert_eq!(at_line.len(), 1);
+        assert!(!at_line[0].outdated);
+    }
+
+    #[test]
+    fn test_falls_back_to_original_line() {
+        // Outdated at the head, but the original line exists in this diff
+        let thread = ReviewThread::new(vec![comment(None, Some(3), "@@ -1,3 +1,3 @@\n }")]);
+
+        let placed = place_threads(vec![thread], &lines());
+        assert!(!placed[&3][0].outdated);
+    }
+
+    #[test]
+    fn test_mismatched_content_is_outdated() {
+        let thread = ReviewThread::new(vec![comment(
+            Some(2),
+            Some(2),
+            "@@ -1,3 +1,3 @@\n+    something_else();",
+        )]);
+
+        let placed = place_threads(vec![thread], &lines());
+        // Outdated threads are collected after the last line
+        let at_end = &placed[&3];
+        assert!(at_end[0].outdated);
+
+        let rows = thread_rows(&at_end[0]);
+        assert!(rows[0].text.ends_with("[outdated]"));
+        assert_eq!(rows.len(), 4); // header, two body lines, footer
+    }
+
+    #[test]
+    fn test_resolved_thread_collapses() {
+        let mut thread = ReviewThread::new(vec![
+            comment(Some(2), Some(2), "@@ -1,3 +1,3 @@\n+    new();"),
+            comment(Some(2), Some(2), "@@ -1,3 +1,3 @@\n+    new();"),
+        ]);
+        thread.is_resolved = true;
+
+        let mut placed = place_threads(vec![thread], &lines());
+        let resolved = &mut placed.get_mut(&2).unwrap()[0];
+        assert!(resolved.collapsed);
+
+        let rows = thread_rows(resolved);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].kind, ThreadRowKind::Collapsed);
+        assert!(rows[0].text.ends_with("(2 comments)"));
+
+        // Expanded resolved threads show the whole conversation
+        resolved.collapsed = false;
+        let rows = thread_rows(resolved);
+        assert!(rows[0].text.ends_with("[resolved]"));
+        assert_eq!(rows.len(), 7); // two comments of three rows, footer
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{
+    github::models::{TimelineItem, TimelineKind},
+    theme::Theme,
+    ui::{markdown::render_markdown, scroll::ScrollState},
+};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Screen listing the pull request's comments, reviews and events in order
+pub struct ConversationView {
+    pub items: Vec<TimelineItem>,
+    /// Whether the timeline has been fetched at least once
+    pub loaded: bool,
+    pub scroll: ScrollState,
+}
+
+impl ConversationView {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            loaded: false,
+            scroll: ScrollState::new(),
+        }
+    }
+
+    pub fn set_items(&mut self, items: Vec<TimelineItem>) {
+        self.items = items;
+        self.loaded = true;
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme, comment_key: &str) {
+        let lines = if self.loaded {
+            timeline_lines(&self.items, theme)
+        } else {
+            vec![Line::from(Span::styled(
+                "Loading conversation...",
+                Style::default().fg(theme.context()),
+            ))]
+        };
+        self.scroll.fit(&lines, area);
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(format!(
+                        " Conversation ({} entries) · {comment_key}: comment ",
+                        self.items.len()
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_focused()))
+                    .style(Style::default().bg(theme.bg()).fg(theme.fg())),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll.offset, 0));
+        f.render_widget(paragraph, area);
+        self.scroll.render_scrollbar(f, area, theme);
+    }
+}
+
+/// Summary of what happened, e.g. "approved these changes"
+fn describe(kind: &TimelineKind) -> String {
+    match kind {
+        TimelineKind::Comment { .. } => "commented".to_string(),
+        TimelineKind::Review { state, .. } => match state.as_str() {
+            "approved" => "approved these changes".to_string(),
+            "changes_requested" => "requested changes".to_string(),
+            "dismissed" => "reviewed (dismissed)".to_string(),
+            _ => "reviewed".to_string(),
+        },
+        TimelineKind::Push { commits } => format!(
+            "pushed {} commit{}",
+            commits.len(),
+            if commits.len() == 1 { "" } else { "s" }
+        ),
+        TimelineKind::ForcePush { commit_id } => match commit_id {
+            Some(sha) => format!("force-pushed to {}", &sha[..sha.len().min(7)]),
+            None => "force-pushed".to_string(),
+        },
+        TimelineKind::Label { name, added: true } => format!("added the label \"{name}\""),
+        TimelineKind::Label { name, added: false } => format!("removed the label \"{name}\""),
+        TimelineKind::ReviewRequest {
+            reviewer,
+            requested: true,
+        } => format!("requested a review from {reviewer}"),
+        TimelineKind::ReviewRequest {
+            reviewer,
+            requested: false,
+        } => format!("removed the review request for {reviewer}"),
+        TimelineKind::Other { event } => event.replace('_', " "),
+    }
+}
+
+fn accent(kind: &TimelineKind, theme: &Theme) -> Color {
+    match kind {
+        TimelineKind::Review { state, .. } if state == "approved" => theme.success(),
+        TimelineKind::Review { state, .. } if state == "changes_requested" => theme.error(),
+        TimelineKind::Comment { .. } | TimelineKind::Review { .. } => theme.info(),
+        TimelineKind::ForcePush { .. } => theme.warning(),
+        _ => theme.context(),
+    }
+}
+
+/// Lay out each entry as a header line followed by its indented body, if any
+fn timeline_lines(items: &[TimelineItem], theme: &Theme) -> Vec<Line<'static>> {
+    if items.is_empty() {
+        return vec![Line::from(Span::styled(
+            "No activity yet.",
+            Style::default()
+                .fg(theme.context())
+                .add_modifier(Modifier::ITALIC),
+        ))];
+    }
+
+    let mut lines = Vec::new();
+    for item in items {
+        let color = accent(&item.kind, theme);
+        lines.push(Line::from(vec![
+            Span::styled("● ", Style::default().fg(color)),
+            Span::styled(
+                item.actor.clone().unwrap_or_else(|| "ghost".to_string()),
+                Style::default().fg(theme.fg()).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" {}", describe(&item.kind)),
+                Style::default().fg(color),
+            ),
+            Span::styled(
+                format!(" · {}", item.created_at.format("%Y-%m-%d %H:%M")),
+                Style::default().fg(theme.subtitle()),
+            ),
+        ]));
+
+        let body: Vec<Line<'static>> = match &item.kind {
+            TimelineKind::Comment { body } | TimelineKind::Review { body, .. } => {
+                render_markdown(body.trim(), theme)
+            }
+            TimelineKind::Push { commits } => commits
+                .iter()
+                .map(|message| {
+                    Line::from(Span::styled(
+                        message.clone(),
+                        Style::default().fg(theme.fg()),
+                    ))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        for line in body {
+            let mut spans = vec![Span::styled("│ ", Style::default().fg(theme.border()))];
+            spans.extend(line.spans);
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn text_of(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_timeline_lines() {
+        let items = vec![
+            TimelineItem {
+                created_at: Utc::now(),
+                actor: Some("alice".to_string()),
+                kind: TimelineKind::Review {
+                    state: "approved".to_string(),
+                    body: "Looks **good**".to_string(),
+                },
+            },
+            TimelineItem {
+                created_at: Utc::now(),
+                actor: Some("bob".to_string()),
+                kind: TimelineKind::Label {
+                    name: "bug".to_string(),
+                    added: true,
+                },
+            },
+        ];
+
+        let texts: Vec<String> = timeline_lines(&items, &Theme::for_tests())
+            .iter()
+            .map(text_of)
+            .collect();
+        assert!(texts[0].starts_with("● alice approved these changes · "));
+        assert_eq!(texts[1], "│ Looks good");
+        assert_eq!(texts[2], "");
+        assert!(texts[3].starts_with("● bob added the label \"bug\" · "));
+        assert_eq!(texts.len(), 5);
+    }
+}
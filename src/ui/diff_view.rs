@@ -1,7 +1,11 @@
 use crate::{
-    github::models::{DiffContent, FileChange, LineType},
+    github::models::{CheckAnnotation, DiffContent, DiffLine, FileChange, LineType, ReviewThread},
     syntax_highlight::{syntect_style_to_ratatui_style, SyntaxHighlighter},
     theme::Theme,
+    ui::{
+        annotation::{self, PlacedAnnotations},
+        comment_thread::{self, PlacedThread},
+    },
 };
 use ratatui::{
     layout::Rect,
@@ -10,7 +14,7 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 pub struct DiffView {
     pub scroll_offset: u16,
@@ -18,6 +22,10 @@ pub struct DiffView {
     pub current_file: Option<FileChange>,
     pub viewport_height: u16,
     pub total_lines: usize,
+    /// Index of the line under the cursor in the full file view
+    pub cursor_line: usize,
+    /// Line where a range selection started, the range runs to the cursor
+    pub selection_anchor: Option<usize>,
     hunk_positions: Vec<usize>,
     syntax_highlighter: Option<SyntaxHighlighter>,
     theme_name: Option<String>,
@@ -25,6 +33,16 @@ pub struct DiffView {
     highlighter_cache: HashMap<String, SyntaxHighlighter>,
     /// Cache of hunk positions per file
     hunk_cache: HashMap<String, Vec<usize>>,
+    /// Review threads of the current file, keyed by the line they are shown below
+    inline_threads: BTreeMap<usize, Vec<PlacedThread>>,
+    /// CI annotations of the current file and the lines they mark
+    annotations: PlacedAnnotations,
+    /// Rendered row of each line in the full file view, empty when nothing is shown inline
+    row_offsets: Vec<usize>,
+    /// Resolved threads the user expanded, by root comment id
+    expanded_threads: HashSet<u64>,
+    /// Lines whose annotation messages the user expanded
+    expanded_annotations: HashSet<usize>,
     // Search state
     pub search_mode: bool,   // true when in search input mode
     pub search_active: bool, // true when search results are shown
@@ -42,11 +60,18 @@ impl DiffView {
             current_file: None,
             viewport_height: 20,
             total_lines: 0,
+            cursor_line: 0,
+            selection_anchor: None,
             hunk_positions: Vec::new(),
             syntax_highlighter: None,
             theme_name: None,
             highlighter_cache: HashMap::new(),
             hunk_cache: HashMap::new(),
+            inline_threads: BTreeMap::new(),
+            annotations: PlacedAnnotations::default(),
+            row_offsets: Vec::new(),
+            expanded_threads: HashSet::new(),
+            expanded_annotations: HashSet::new(),
             search_mode: false,
             search_active: false,
             search_query: String::new(),
@@ -93,6 +118,12 @@ impl DiffView {
 
         self.current_file = file;
         self.scroll_offset = 0;
+        self.cursor_line = 0;
+        self.selection_anchor = None;
+        self.inline_threads.clear();
+        self.annotations = PlacedAnnotations::default();
+        self.expanded_annotations.clear();
+        self.row_offsets.clear();
         self.update_max_scroll();
         self.scroll_to_first_change();
     }
@@ -113,11 +144,157 @@ impl DiffView {
         }
     }
 
+    /// Show review threads below the lines they belong to in the current file
+    pub fn set_file_threads(&mut self, threads: Vec<ReviewThread>) {
+        self.inline_threads = match self
+            .current_file
+            .as_ref()
+            .and_then(|f| f.diff_content.as_ref())
+        {
+            Some(diff) if !threads.is_empty() => {
+                comment_thread::place_threads(threads, &diff.full_file_view)
+            }
+            _ => BTreeMap::new(),
+        };
+        self.layout_inline_rows();
+    }
+
+    /// Mark the lines CI annotations refer to and show their messages below them
+    pub fn set_file_annotations(&mut self, annotations: Vec<CheckAnnotation>) {
+        self.annotations = match self
+            .current_file
+            .as_ref()
+            .and_then(|f| f.diff_content.as_ref())
+        {
+            Some(diff) if !annotations.is_empty() => {
+                annotation::place_annotations(annotations, &diff.full_file_view)
+            }
+            _ => PlacedAnnotations::default(),
+        };
+        self.layout_inline_rows();
+    }
+
+    /// The first review thread shown below the line under the cursor
+    pub fn selected_thread(&self) -> Option<&ReviewThread> {
+        self.inline_threads
+            .get(&self.cursor_line)?
+            .first()
+            .map(|placed| &placed.thread)
+    }
+
+    /// Expand or collapse the resolved thread below the line under the cursor,
+    /// or the annotation messages if there is no resolved thread
+    pub fn toggle_thread_expanded(&mut self) {
+        if let Some(thread) = self.selected_thread().filter(|t| t.is_resolved) {
+            let root_id = thread.root().id;
+            if !self.expanded_threads.remove(&root_id) {
+                self.expanded_threads.insert(root_id);
+            }
+        } else if self.annotations.by_line.contains_key(&self.cursor_line) {
+            if !self.expanded_annotations.remove(&self.cursor_line) {
+                self.expanded_annotations.insert(self.cursor_line);
+            }
+        } else {
+            return;
+        }
+        self.layout_inline_rows();
+    }
+
+    /// Recompute the rows taken by review threads and annotations.
+    /// The cursor keeps its position on screen while rows are inserted or removed.
+    fn layout_inline_rows(&mut self) {
+        let cursor_screen_row = self
+            .row_of(self.cursor_line)
+            .saturating_sub(self.scroll_offset as usize);
+
+        for placed in self.inline_threads.values_mut().flatten() {
+            placed.collapsed = placed.thread.is_resolved
+                && !self.expanded_threads.contains(&placed.thread.root().id);
+        }
+
+        self.row_offsets.clear();
+        if !self.inline_threads.is_empty() || !self.annotations.by_line.is_empty() {
+            let mut row = 0;
+            for line_idx in 0..self.line_count() {
+                self.row_offsets.push(row);
+                row += 1 + self.inline_row_count(line_idx);
+            }
+        }
+
+        self.update_max_scroll();
+        self.scroll_offset = (self
+            .row_of(self.cursor_line)
+            .saturating_sub(cursor_screen_row) as u16)
+            .min(self.max_scroll);
+    }
+
+    /// Number of review thread and annotation rows shown below a line
+    fn inline_row_count(&self, line_idx: usize) -> usize {
+        let thread_rows: usize = self
+            .inline_threads
+            .get(&line_idx)
+            .map(|threads| {
+                threads
+                    .iter()
+                    .map(|t| comment_thread::thread_rows(t).len())
+                    .sum()
+            })
+            .unwrap_or(0);
+        let expanded = self.expanded_annotations.contains(&line_idx);
+        let annotation_rows: usize = self
+            .annotations
+            .by_line
+            .get(&line_idx)
+            .map(|annotations| {
+                annotations
+                    .iter()
+                    .map(|a| annotation::annotation_rows(a, expanded).len())
+                    .sum()
+            })
+            .unwrap_or(0);
+        thread_rows + annotation_rows
+    }
+
+    /// Rendered row of a line, accounting for review threads and annotations shown above it
+    fn row_of(&self, line_idx: usize) -> usize {
+        self.row_offsets.get(line_idx).copied().unwrap_or(line_idx)
+    }
+
+    /// Line shown at (or, inside a thread, right above) a rendered row
+    fn line_at_row(&self, row: usize) -> usize {
+        if self.row_offsets.is_empty() {
+            return row;
+        }
+        self.row_offsets
+            .partition_point(|&r| r <= row)
+            .saturating_sub(1)
+    }
+
+    /// Number of lines the cursor can move over
+    fn line_count(&self) -> usize {
+        match self.current_file {
+            Some(ref file) => {
+                if let Some(ref diff) = file.diff_content {
+                    diff.full_file_view.len()
+                } else if let Some(ref patch) = file.patch {
+                    patch.lines().count()
+                } else {
+                    0
+                }
+            }
+            None => 0,
+        }
+    }
+
     fn update_max_scroll(&mut self) {
         if let Some(ref file) = self.current_file {
             if let Some(ref diff) = file.diff_content {
-                // Use the full file view line count
-                self.total_lines = diff.full_file_view.len();
+                // Use the full file view line count, plus any inline threads and annotations
+                let last = diff.full_file_view.len().saturating_sub(1);
+                self.total_lines = match self.row_offsets.last() {
+                    Some(&row) => row + 1 + self.inline_row_count(last),
+                    None => diff.full_file_view.len(),
+                };
             } else if let Some(ref patch) = file.patch {
                 self.total_lines = patch.lines().count();
             } else {
@@ -134,6 +311,7 @@ impl DiffView {
 
     pub fn scroll_up(&mut self, amount: u16) {
         self.scroll_offset = self.scroll_offset.saturating_sub(amount);
+        self.clamp_cursor_to_viewport();
     }
 
     pub fn scroll_down(&mut self, amount: u16) {
@@ -141,6 +319,77 @@ impl DiffView {
             .scroll_offset
             .saturating_add(amount)
             .min(self.max_scroll);
+        self.clamp_cursor_to_viewport();
+    }
+
+    /// Move the line cursor up, scrolling to keep it visible
+    pub fn cursor_up(&mut self) {
+        self.cursor_line = self.cursor_line.saturating_sub(1);
+        let row = self.row_of(self.cursor_line);
+        if row < self.scroll_offset as usize {
+            self.scroll_offset = row as u16;
+        }
+    }
+
+    /// Move the line cursor down, scrolling to keep it visible
+    pub fn cursor_down(&mut self) {
+        if self.cursor_line + 1 < self.line_count() {
+            self.cursor_line += 1;
+        }
+        let row = self.row_of(self.cursor_line);
+        let bottom = self.scroll_offset as usize + self.viewport_height as usize;
+        if row >= bottom {
+            self.scroll_down((row + 1 - bottom) as u16);
+        }
+    }
+
+    /// Keep the cursor inside the visible viewport after scrolling
+    fn clamp_cursor_to_viewport(&mut self) {
+        let last = self.line_count().saturating_sub(1);
+        let top_row = self.scroll_offset as usize;
+        let bottom_row = (top_row + self.viewport_height as usize).saturating_sub(1);
+
+        // First line starting at or below the top row, and the line shown on the bottom row
+        let top = (0..=last)
+            .find(|&idx| self.row_of(idx) >= top_row)
+            .unwrap_or(last);
+        let bottom = self.line_at_row(bottom_row).min(last);
+        self.cursor_line = self.cursor_line.clamp(top, bottom.max(top));
+    }
+
+    /// Start selecting a range of lines at the cursor, or stop if already selecting
+    pub fn toggle_selection(&mut self) {
+        self.selection_anchor = match self.selection_anchor {
+            Some(_) => None,
+            None => Some(self.cursor_line),
+        };
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection_anchor = None;
+    }
+
+    /// First and last line index of the selection, or the cursor line when not selecting
+    pub fn selected_range(&self) -> (usize, usize) {
+        match self.selection_anchor {
+            Some(anchor) => (anchor.min(self.cursor_line), anchor.max(self.cursor_line)),
+            None => (self.cursor_line, self.cursor_line),
+        }
+    }
+
+    /// The diff lines in the selected range
+    pub fn selected_lines(&self) -> &[DiffLine] {
+        let Some(diff) = self
+            .current_file
+            .as_ref()
+            .and_then(|f| f.diff_content.as_ref())
+        else {
+            return &[];
+        };
+        let (start, end) = self.selected_range();
+        diff.full_file_view
+            .get(start..=end.min(diff.full_file_view.len().saturating_sub(1)))
+            .unwrap_or(&[])
     }
 
     pub fn page_up(&mut self) {
@@ -155,10 +404,12 @@ impl DiffView {
 
     pub fn scroll_to_top(&mut self) {
         self.scroll_offset = 0;
+        self.cursor_line = 0;
     }
 
     pub fn scroll_to_bottom(&mut self) {
         self.scroll_offset = self.max_scroll;
+        self.cursor_line = self.line_count().saturating_sub(1);
     }
 
     /// Jump to the next hunk in the diff
@@ -172,7 +423,7 @@ impl DiffView {
         // Find the next hunk position after the current scroll position
         // We need to check if the next hunk's adjusted position (with context) is different from current
         for &hunk_pos in &self.hunk_positions {
-            let target_line = hunk_pos.saturating_sub(2);
+            let target_line = self.row_of(hunk_pos).saturating_sub(2);
             if target_line > current_line {
                 // Jump to this hunk, but don't exceed max_scroll if content fits in viewport
                 self.scroll_offset = if self.total_lines > self.viewport_height as usize {
@@ -180,6 +431,7 @@ impl DiffView {
                 } else {
                     target_line as u16
                 };
+                self.cursor_line = hunk_pos;
                 return;
             }
         }
@@ -187,12 +439,13 @@ impl DiffView {
         // If we're at or past the last hunk, wrap around to the first
         if !self.hunk_positions.is_empty() {
             let first_hunk = self.hunk_positions[0];
-            let target_line = first_hunk.saturating_sub(2);
+            let target_line = self.row_of(first_hunk).saturating_sub(2);
             self.scroll_offset = if self.total_lines > self.viewport_height as usize {
                 (target_line as u16).min(self.max_scroll)
             } else {
                 target_line as u16
             };
+            self.cursor_line = first_hunk;
         }
     }
 
@@ -207,7 +460,7 @@ impl DiffView {
         // Find the previous hunk position before the current scroll position
         // We check against the adjusted target position to find the previous visible hunk
         for &hunk_pos in self.hunk_positions.iter().rev() {
-            let target_line = hunk_pos.saturating_sub(2);
+            let target_line = self.row_of(hunk_pos).saturating_sub(2);
             if target_line < current_line {
                 // Jump to this hunk, but don't exceed max_scroll if content fits in viewport
                 self.scroll_offset = if self.total_lines > self.viewport_height as usize {
@@ -215,6 +468,7 @@ impl DiffView {
                 } else {
                     target_line as u16
                 };
+                self.cursor_line = hunk_pos;
                 return;
             }
         }
@@ -222,12 +476,13 @@ impl DiffView {
         // If we're at or before the first hunk, optionally wrap around to the last
         if !self.hunk_positions.is_empty() {
             let last_hunk = *self.hunk_positions.last().unwrap();
-            let target_line = last_hunk.saturating_sub(2);
+            let target_line = self.row_of(last_hunk).saturating_sub(2);
             self.scroll_offset = if self.total_lines > self.viewport_height as usize {
                 (target_line as u16).min(self.max_scroll)
             } else {
                 target_line as u16
             };
+            self.cursor_line = last_hunk;
         }
     }
 
@@ -282,8 +537,9 @@ impl DiffView {
                 for (index, line) in diff.full_file_view.iter().enumerate() {
                     if matches!(line.line_type, LineType::Addition | LineType::Deletion) {
                         // Scroll to this line, with a small offset to show some context
-                        let target_line = index.saturating_sub(2); // Show 2 lines of context before if possible
+                        let target_line = self.row_of(index).saturating_sub(2); // Show 2 lines of context before if possible
                         self.scroll_offset = (target_line as u16).min(self.max_scroll);
+                        self.cursor_line = index;
                         break;
                     }
                 }
@@ -361,7 +617,7 @@ impl DiffView {
         self.search_mode = false; // Exit input mode
     }
 
-    fn find_matches(&mut self, lines: &[crate::github::models::DiffLine]) {
+    fn find_matches(&mut self, lines: &[DiffLine]) {
         let query_lower = self.search_query.to_lowercase();
 
         for (line_idx, line) in lines.iter().enumerate() {
@@ -448,8 +704,10 @@ impl DiffView {
         if let Some(idx) = self.current_match_index {
             if let Some(&(line_idx, _, _)) = self.search_matches.get(idx) {
                 // Scroll to center the match in the viewport if possible
-                let target_line = (line_idx as u16).saturating_sub(self.viewport_height / 2);
+                let target_line =
+                    (self.row_of(line_idx) as u16).saturating_sub(self.viewport_height / 2);
                 self.scroll_offset = target_line.min(self.max_scroll);
+                self.cursor_line = line_idx;
             }
         }
     }
@@ -678,6 +936,30 @@ impl DiffView {
                 "    [           : Jump to previous hunk",
                 Style::default().fg(theme.fg()),
             )));
+            lines.push(Line::from(Span::styled(
+                "    c           : Comment on the line under the cursor",
+                Style::default().fg(theme.fg()),
+            )));
+            lines.push(Line::from(Span::styled(
+                "    v           : Select a range of lines to comment on",
+                Style::default().fg(theme.fg()),
+            )));
+            lines.push(Line::from(Span::styled(
+                "    s           : Suggest a change to the selected lines",
+                Style::default().fg(theme.fg()),
+            )));
+            lines.push(Line::from(Span::styled(
+                "    R           : Reply to the thread under the cursor",
+                Style::default().fg(theme.fg()),
+            )));
+            lines.push(Line::from(Span::styled(
+                "    x           : Resolve or unresolve the thread",
+                Style::default().fg(theme.fg()),
+            )));
+            lines.push(Line::from(Span::styled(
+                "    e           : Expand or collapse a resolved thread",
+                Style::default().fg(theme.fg()),
+            )));
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "  Search:",
@@ -880,6 +1162,23 @@ impl DiffView {
                 }
             };
 
+            // Mark lines with CI annotations in the otherwise blank start of the gutter
+            let annotation_marker = self
+                .annotations
+                .markers
+                .get(&line_idx)
+                .filter(|_| line_number_str.starts_with(' '))
+                .map(|&level| {
+                    Span::styled(
+                        "▌",
+                        Style::default().fg(annotation::level_color(level, theme)),
+                    )
+                });
+            let line_number_str = match annotation_marker {
+                Some(_) => line_number_str[1..].to_string(),
+                None => line_number_str,
+            };
+
             // Determine the prefix character and base style based on the line type
             let (prefix, base_style, background_color) = match diff_line.line_type {
                 LineType::Addition => (
@@ -1011,7 +1310,40 @@ impl DiffView {
                 }
             };
 
+            // Highlight the line under the cursor and any selected range
+            let (selection_start, selection_end) = self.selected_range();
+            let formatted_line = if (selection_start..=selection_end).contains(&line_idx) {
+                formatted_line
+                    .into_iter()
+                    .map(|span| {
+                        let style = span.style.bg(theme.sidebar_selected());
+                        span.style(style)
+                    })
+                    .collect()
+            } else {
+                formatted_line
+            };
+
+            let formatted_line = match annotation_marker {
+                Some(marker) => std::iter::once(marker).chain(formatted_line).collect(),
+                None => formatted_line,
+            };
             lines.push(Line::from(formatted_line));
+
+            // Review threads attached to this line
+            if let Some(threads) = self.inline_threads.get(&line_idx) {
+                for thread in threads {
+                    lines.extend(comment_thread::render_thread(thread, theme));
+                }
+            }
+
+            // CI annotations ending on this line
+            if let Some(annotations) = self.annotations.by_line.get(&line_idx) {
+                let expanded = self.expanded_annotations.contains(&line_idx);
+                for item in annotations {
+                    lines.extend(annotation::render_annotation(item, expanded, theme));
+                }
+            }
         }
 
         if lines.is_empty() {
@@ -1025,7 +1357,7 @@ impl DiffView {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::github::models::{DiffContent, DiffLine, FileStatus, LineType};
+    use crate::github::models::{DiffContent, FileStatus, LineType};
 
     #[test]
     fn test_hunk_navigation_with_multiple_hunks() {
@@ -1663,4 +1995,158 @@ mod tests {
         // Expected offset would be around 18 (20 - viewport_height/2)
         assert!(diff_view.scroll_offset > 15);
     }
+
+    #[test]
+    fn test_cursor_follows_scroll_and_selection() {
+        let mut diff_view = DiffView::new();
+        diff_view.viewport_height = 3;
+
+        let lines: Vec<DiffLine> = (0..10)
+            .map(|i| DiffLine {
+                line_type: LineType::Context,
+                content: format!("line {i}"),
+                old_line_no: Some(i + 1),
+                new_line_no: Some(i + 1),
+            })
+            .collect();
+
+        let file_change = FileChange {
+            filename: "test.txt".to_string(),
+            status: FileStatus::Modified,
+            additions: 0,
+            deletions: 0,
+            patch: None,
+            raw_content: None,
+            diff_content: Some(DiffContent {
+                hunks: vec![],
+                full_file_view: lines,
+            }),
+        };
+
+        diff_view.set_file(Some(file_change));
+        assert_eq!(diff_view.cursor_line, 0);
+
+        // Moving past the bottom of the viewport scrolls the view
+        diff_view.cursor_down();
+        diff_view.cursor_down();
+        diff_view.cursor_down();
+        assert_eq!(diff_view.cursor_line, 3);
+        assert_eq!(diff_view.scroll_offset, 1);
+        assert_eq!(diff_view.selected_lines()[0].content, "line 3");
+
+        // Scrolling the view drags the cursor along
+        diff_view.scroll_down(4);
+        assert_eq!(diff_view.scroll_offset, 5);
+        assert_eq!(diff_view.cursor_line, 5);
+
+        // Moving above the viewport scrolls back up
+        diff_view.cursor_up();
+        assert_eq!(diff_view.cursor_line, 4);
+        assert_eq!(diff_view.scroll_offset, 4);
+
+        diff_view.scroll_to_bottom();
+        assert_eq!(diff_view.cursor_line, 9);
+        diff_view.cursor_down();
+        assert_eq!(diff_view.cursor_line, 9);
+
+        // Selecting upwards from the cursor covers the lines in between
+        diff_view.toggle_selection();
+        diff_view.cursor_up();
+        diff_view.cursor_up();
+        assert_eq!(diff_view.selected_range(), (7, 9));
+        let contents: Vec<_> = diff_view
+            .selected_lines()
+            .iter()
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(contents, ["line 7", "line 8", "line 9"]);
+
+        diff_view.toggle_selection();
+        assert_eq!(diff_view.selected_range(), (7, 7));
+    }
+
+    #[test]
+    fn test_inline_threads_shift_rows() {
+        use crate::github::models::{DiffSide, ReviewComment};
+
+        let mut diff_view = DiffView::new();
+        diff_view.viewport_height = 4;
+
+        let lines: Vec<DiffLine> = (0..10)
+            .map(|i| DiffLine {
+                line_type: LineType::Context,
+                content: format!("line {i}"),
+                old_line_no: Some(i + 1),
+                new_line_no: Some(i + 1),
+            })
+            .collect();
+
+        let file_change = FileChange {
+            filename: "test.txt".to_string(),
+            status: FileStatus::Modified,
+            additions: 0,
+            deletions: 0,
+            patch: None,
+            raw_content: None,
+            diff_content: Some(DiffContent {
+                hunks: vec![],
+                full_file_view: lines,
+            }),
+        };
+
+        // A one-line comment on "line 1" (new line 2), rendered as three rows
+        let thread = ReviewThread::new(vec![ReviewComment {
+            id: 1,
+            path: "test.txt".to_string(),
+            body: "Looks off".to_string(),
+            user: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            line: Some(2),
+            original_line: Some(2),
+            side: Some(DiffSide::Right),
+            commit_id: "abc".to_string(),
+            original_commit_id: "abc".to_string(),
+            in_reply_to_id: None,
+            diff_hunk: "@@ -1,2 +1,2 @@\n line 0\n line 1".to_string(),
+        }]);
+
+        diff_view.set_file(Some(file_change));
+        diff_view.set_file_threads(vec![thread]);
+        assert_eq!(diff_view.total_lines, 13);
+        assert_eq!(diff_view.row_of(1), 1);
+        assert_eq!(diff_view.row_of(2), 5);
+        assert_eq!(diff_view.line_at_row(3), 1);
+
+        // The cursor skips over the thread rows
+        diff_view.cursor_down();
+        diff_view.cursor_down();
+        assert_eq!(diff_view.cursor_line, 2);
+        assert_eq!(diff_view.scroll_offset, 2);
+        assert_eq!(diff_view.selected_lines()[0].content, "line 2");
+
+        // Clearing the threads restores the plain layout
+        diff_view.set_file_threads(Vec::new());
+        assert_eq!(diff_view.total_lines, 10);
+        assert_eq!(diff_view.row_of(2), 2);
+
+        // A two-line annotation message on new lines 4-5 shows a summary row below line 5
+        diff_view.set_file_annotations(vec![CheckAnnotation {
+            path: "test.txt".to_string(),
+            start_line: 4,
+            end_line: 5,
+            annotation_level: crate::github::models::AnnotationLevel::Failure,
+            title: None,
+            message: "mismatched types\nexpected `u8`".to_string(),
+            check_name: "build".to_string(),
+        }]);
+        assert_eq!(diff_view.total_lines, 11);
+        assert_eq!(diff_view.row_of(5), 6);
+
+        // Expanding it from the annotated line reveals the rest of the message
+        diff_view.cursor_line = 4;
+        diff_view.toggle_thread_expanded();
+        assert_eq!(diff_view.total_lines, 12);
+        assert_eq!(diff_view.row_of(5), 7);
+    }
 }
@@ -35,6 +35,25 @@ impl AppLayout {
         (content_chunks[0], content_chunks[1], main_chunks[1])
     }
 
+    /// Take a line off the top of the screen for a banner
+    pub fn split_banner(area: Rect) -> (Rect, Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+
+        (chunks[0], chunks[1])
+    }
+
+    pub fn render_banner(f: &mut Frame, area: Rect, text: &str, theme: &Theme) {
+        let banner = Paragraph::new(format!(" ⚠ {text}")).style(
+            Style::default()
+                .fg(theme.warning())
+                .add_modifier(Modifier::BOLD),
+        );
+        f.render_widget(banner, area);
+    }
+
     pub fn render_loading_checklist(
         f: &mut Frame,
         area: Rect,
@@ -143,7 +162,7 @@ impl AppLayout {
     }
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -0,0 +1,288 @@
+use crate::theme::Theme;
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Render GitHub-flavoured markdown into styled lines.
+/// Covers what PR descriptions commonly use: headings, lists, task lists, quotes,
+/// fenced code, rules and inline emphasis, code and links.
+pub fn render_markdown(text: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    let mut in_html_comment = false;
+
+    for raw in text.lines() {
+        let trimmed = raw.trim();
+
+        // PR templates often leave hints in HTML comments, which GitHub doesn't display
+        if in_html_comment {
+            if trimmed.contains("-->") {
+                in_html_comment = false;
+            }
+            continue;
+        }
+        if trimmed.starts_with("<!--") {
+            in_html_comment = !trimmed.contains("-->");
+            continue;
+        }
+
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::from(vec![
+                Span::styled("  │ ", Style::default().fg(theme.border())),
+                Span::styled(raw.to_string(), Style::default().fg(theme.info())),
+            ]));
+            continue;
+        }
+
+        lines.push(render_block_line(raw, theme));
+    }
+
+    // Collapse runs of blank lines left behind by skipped comments
+    lines.dedup_by(|a, b| a.width() == 0 && b.width() == 0);
+    lines
+}
+
+/// Render one line outside of a code block
+fn render_block_line(raw: &str, theme: &Theme) -> Line<'static> {
+    let trimmed = raw.trim_start();
+    let indent = " ".repeat(raw.len() - trimmed.len());
+    let text_style = Style::default().fg(theme.fg());
+
+    // Headings
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+        let mut style = Style::default()
+            .fg(theme.header())
+            .add_modifier(Modifier::BOLD);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        return Line::from(render_inline(trimmed[level..].trim(), style, theme));
+    }
+
+    // Horizontal rules
+    let rule: Vec<char> = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    if rule.len() >= 3 && matches!(rule[0], '-' | '*' | '_') && rule.iter().all(|&c| c == rule[0]) {
+        return Line::from(Span::styled(
+            "─".repeat(40),
+            Style::default().fg(theme.border()),
+        ));
+    }
+
+    // Block quotes
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        let style = Style::default()
+            .fg(theme.subtitle())
+            .add_modifier(Modifier::ITALIC);
+        let mut spans = vec![Span::styled(
+            format!("{indent}▎ "),
+            Style::default().fg(theme.border()),
+        )];
+        spans.extend(render_inline(quote.trim_start(), style, theme));
+        return Line::from(spans);
+    }
+
+    // Task lists and bullet lists
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = trimmed.strip_prefix(marker) {
+            let (bullet, item) = if let Some(rest) = item.strip_prefix("[ ] ") {
+                ("☐ ", rest)
+            } else if let Some(rest) = item
+                .strip_prefix("[x] ")
+                .or_else(|| item.strip_prefix("[X] "))
+            {
+                ("☑ ", rest)
+            } else {
+                ("• ", item)
+            };
+            let mut spans = vec![Span::styled(
+                format!("{indent}  {bullet}"),
+                Style::default().fg(theme.nav_active()),
+            )];
+            spans.extend(render_inline(item, text_style, theme));
+            return Line::from(spans);
+        }
+    }
+
+    // Ordered lists
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(item) = trimmed[digits..].strip_prefix(". ") {
+            let mut spans = vec![Span::styled(
+                format!("{indent}  {}. ", &trimmed[..digits]),
+                Style::default().fg(theme.nav_active()),
+            )];
+            spans.extend(render_inline(item, text_style, theme));
+            return Line::from(spans);
+        }
+    }
+
+    let mut spans = Vec::new();
+    if !indent.is_empty() {
+        spans.push(Span::raw(indent));
+    }
+    spans.extend(render_inline(trimmed, text_style, theme));
+    Line::from(spans)
+}
+
+/// Render inline markup: `code`, **bold**, *italic*, ~~strikethrough~~ and [links](url)
+fn render_inline(text: &str, base: Style, theme: &Theme) -> Vec<Span<'static>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut buf = String::new();
+    let mut modifiers = Modifier::empty();
+    let mut i = 0;
+
+    let flush = |buf: &mut String, spans: &mut Vec<Span<'static>>, modifiers: Modifier| {
+        if !buf.is_empty() {
+            spans.push(Span::styled(
+                std::mem::take(buf),
+                base.add_modifier(modifiers),
+            ));
+        }
+    };
+
+    while i < chars.len() {
+        let rest = &chars[i..];
+        let toggle = match rest {
+            ['*', '*', ..] | ['_', '_', ..] => Some((Modifier::BOLD, 2)),
+            ['~', '~', ..] => Some((Modifier::CROSSED_OUT, 2)),
+            // Lone underscores are too common in identifiers to treat as emphasis
+            ['*', next, ..] if !next.is_whitespace() || modifiers.contains(Modifier::ITALIC) => {
+                Some((Modifier::ITALIC, 1))
+            }
+            _ => None,
+        };
+
+        if let Some((modifier, len)) = toggle {
+            // Only open an emphasis that is closed later on the line
+            let marker = &rest[..len];
+            let closes =
+                modifiers.contains(modifier) || rest[len..].windows(len).any(|w| w == marker);
+            if closes {
+                flush(&mut buf, &mut spans, modifiers);
+                modifiers.toggle(modifier);
+                i += len;
+                continue;
+            }
+        }
+
+        match rest {
+            ['`', ..] => {
+                if let Some(end) = rest[1..].iter().position(|&c| c == '`') {
+                    flush(&mut buf, &mut spans, modifiers);
+                    let code: String = rest[1..1 + end].iter().collect();
+                    spans.push(Span::styled(code, Style::default().fg(theme.info())));
+                    i += end + 2;
+                    continue;
+                }
+            }
+            ['[', ..] => {
+                if let Some(link) = parse_link(rest) {
+                    flush(&mut buf, &mut spans, modifiers);
+                    spans.push(Span::styled(
+                        link.text,
+                        Style::default()
+                            .fg(theme.info())
+                            .add_modifier(Modifier::UNDERLINED),
+                    ));
+                    i += link.len;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+
+        buf.push(chars[i]);
+        i += 1;
+    }
+
+    flush(&mut buf, &mut spans, modifiers);
+    spans
+}
+
+struct Link {
+    text: String,
+    /// Number of characters the link takes up in the source
+    len: usize,
+}
+
+/// Parse `[text](url)` at the start of `chars`
+fn parse_link(chars: &[char]) -> Option<Link> {
+    let close = chars.iter().position(|&c| c == ']')?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    let end = close + 2 + chars[close + 2..].iter().position(|&c| c == ')')?;
+    Some(Link {
+        text: chars[1..close].iter().collect(),
+        len: end + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_of(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_block_elements() {
+        let md = "# Summary\r\n<!-- template hint\r\nspanning lines -->\r\n- [x] Tests\r\n* item\r\n2. second\r\n> quoted\r\n```rust\r\nfn main() {}\r\n```";
+        let lines = render_markdown(md, &Theme::for_tests());
+        let texts: Vec<String> = lines.iter().map(text_of).collect();
+
+        assert_eq!(
+            texts,
+            [
+                "Summary",
+                "  ☑ Tests",
+                "  • item",
+                "  2. second",
+                "▎ quoted",
+                "  │ fn main() {}",
+            ]
+        );
+        assert!(lines[0].spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_inline_markup() {
+        let theme = Theme::for_tests();
+        let spans = render_inline(
+            "Use **bold**, `code` and [docs](https://example.com) in snake_case_names",
+            Style::default(),
+            &theme,
+        );
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(
+            texts,
+            [
+                "Use ",
+                "bold",
+                ", ",
+                "code",
+                " and ",
+                "docs",
+                " in snake_case_names"
+            ]
+        );
+        assert!(spans[1].style.add_modifier.contains(Modifier::BOLD));
+        assert!(spans[5].style.add_modifier.contains(Modifier::UNDERLINED));
+
+        // Unclosed markers are kept as text
+        let spans = render_inline("2 * 3 = 6", Style::default(), &theme);
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].content, "2 * 3 = 6");
+    }
+}
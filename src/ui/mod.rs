@@ -1,9 +1,23 @@
+pub mod annotation;
+pub mod checks;
+pub mod comment_input;
+pub mod comment_thread;
+pub mod conversation;
 pub mod diff_view;
 pub mod layout;
+pub mod markdown;
 pub mod navigation;
+pub mod overview;
+pub mod review_dialog;
+pub mod scroll;
 pub mod sidebar;
 
+pub use checks::ChecksView;
+pub use comment_input::{CommentInput, CommentTarget};
+pub use conversation::ConversationView;
 pub use diff_view::DiffView;
 pub use layout::AppLayout;
 pub use navigation::Navigation;
+pub use overview::PrOverview;
+pub use review_dialog::ReviewDialog;
 pub use sidebar::Sidebar;
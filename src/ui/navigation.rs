@@ -1,4 +1,13 @@
-use crate::{app::FocusedPane, github::models::Commit, keybindings::KeyBindings, theme::Theme};
+use crate::{
+    app::{FocusedPane, StatusMessage},
+    github::{
+        models::{CheckState, Commit, CommitChecks},
+        rate_limit::RateLimit,
+    },
+    keybindings::KeyBindings,
+    theme::Theme,
+    ui::checks::state_color,
+};
 use ratatui::{
     layout::{Alignment, Rect},
     style::{Modifier, Style},
@@ -6,11 +15,18 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use std::collections::HashMap;
 
 pub struct Navigation {
     pub current_commit: usize,
     pub total_commits: usize,
     pub commits: Vec<Commit>,
+    /// Number of draft comments waiting in the pending review
+    pub pending_comments: usize,
+    /// Combined CI result of each commit, keyed by SHA
+    pub check_states: HashMap<String, CheckState>,
+    /// GitHub API quota left, once a response reported it
+    pub rate_limit: Option<RateLimit>,
 }
 
 impl Navigation {
@@ -20,9 +36,29 @@ impl Navigation {
             current_commit: if total > 0 { 1 } else { 0 },
             total_commits: total,
             commits,
+            pending_comments: 0,
+            check_states: HashMap::new(),
+            rate_limit: None,
         }
     }
 
+    pub fn set_checks(&mut self, checks: &HashMap<String, CommitChecks>) {
+        self.check_states = checks
+            .iter()
+            .filter_map(|(sha, checks)| Some((sha.clone(), checks.state()?)))
+            .collect();
+    }
+
+    fn check_badge(&self, sha: &str, theme: &Theme) -> Option<Span<'static>> {
+        let state = *self.check_states.get(sha)?;
+        Some(Span::styled(
+            state.symbol(),
+            Style::default()
+                .fg(state_color(state, theme))
+                .add_modifier(Modifier::BOLD),
+        ))
+    }
+
     pub fn next_commit(&mut self) -> bool {
         if self.current_commit < self.total_commits {
             self.current_commit += 1;
@@ -64,9 +100,10 @@ impl Navigation {
         theme: &Theme,
         focused_pane: FocusedPane,
         keybindings: &KeyBindings,
+        status: Option<&StatusMessage>,
     ) {
         let commit_info = if let Some(commit) = self.get_current_commit() {
-            let short_sha = &commit.sha[..7];
+            let short_sha = commit.short_sha();
             let message = commit
                 .commit
                 .message
@@ -76,16 +113,41 @@ impl Navigation {
                 .chars()
                 .take(50)
                 .collect::<String>();
-            format!(
-                " [{}/{}] {} - {} ",
-                self.current_commit, self.total_commits, short_sha, message
-            )
+            let mut spans = vec![Span::raw(format!(
+                " [{}/{}] {} ",
+                self.current_commit, self.total_commits, short_sha
+            ))];
+            if let Some(badge) = self.check_badge(&commit.sha, theme) {
+                spans.push(badge);
+                spans.push(Span::raw(" "));
+            }
+            spans.push(Span::raw(format!("- {message} ")));
+            Line::from(spans)
         } else if self.total_commits == 0 {
-            " No commits in this PR ".to_string()
+            Line::from(" No commits in this PR ")
         } else {
-            format!(" Commit {}/{} ", self.current_commit, self.total_commits)
+            Line::from(format!(
+                " Commit {}/{} ",
+                self.current_commit, self.total_commits
+            ))
         };
 
+        // One badge per commit so a failing commit stands out while stepping through them
+        let mut ci_strip = Vec::new();
+        if !self.check_states.is_empty() {
+            ci_strip.push(Span::raw(" CI "));
+            for (i, commit) in self.commits.iter().enumerate() {
+                let mut badge = self
+                    .check_badge(&commit.sha, theme)
+                    .unwrap_or_else(|| Span::styled("·", Style::default().fg(theme.context())));
+                if i + 1 == self.current_commit {
+                    badge = badge.patch_style(Style::default().add_modifier(Modifier::UNDERLINED));
+                }
+                ci_strip.push(badge);
+            }
+            ci_strip.push(Span::raw(" "));
+        }
+
         // Add focus indicator
         let focus_indicator = match focused_pane {
             FocusedPane::Sidebar => " [Focus: Sidebar] ",
@@ -103,6 +165,12 @@ impl Navigation {
         let top_bottom = format!("{}/{}", display_keys.go_to_top, display_keys.go_to_bottom);
         let hunks = format!("{}/{}", display_keys.prev_hunk, display_keys.next_hunk);
 
+        let review_label = if self.pending_comments > 0 {
+            format!(" Review ({})  ", self.pending_comments)
+        } else {
+            " Review  ".to_string()
+        };
+
         let nav_controls = vec![Line::from(vec![
             Span::raw(" "),
             Span::styled(
@@ -154,6 +222,41 @@ impl Navigation {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(" Search  ", Style::default().fg(theme.nav_fg())),
+            Span::styled(
+                &display_keys.add_comment,
+                Style::default()
+                    .fg(theme.nav_active())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Comment  ", Style::default().fg(theme.nav_fg())),
+            Span::styled(
+                &display_keys.submit_review,
+                Style::default()
+                    .fg(theme.nav_active())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(review_label, Style::default().fg(theme.nav_fg())),
+            Span::styled(
+                &display_keys.toggle_overview,
+                Style::default()
+                    .fg(theme.nav_active())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Overview  ", Style::default().fg(theme.nav_fg())),
+            Span::styled(
+                &display_keys.toggle_conversation,
+                Style::default()
+                    .fg(theme.nav_active())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Conversation  ", Style::default().fg(theme.nav_fg())),
+            Span::styled(
+                &display_keys.toggle_checks,
+                Style::default()
+                    .fg(theme.nav_active())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Checks  ", Style::default().fg(theme.nav_fg())),
             Span::styled(
                 &display_keys.quit,
                 Style::default()
@@ -169,14 +272,51 @@ impl Navigation {
             ),
         ])];
 
+        let mut block = Block::default()
+            .title(commit_info)
+            .title(Line::from(ci_strip).right_aligned())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border()))
+            .style(Style::default().bg(theme.nav_bg()).fg(theme.nav_fg()));
+
+        // Show the API quota left on the bottom border, highlighted once it runs low
+        if let Some(rate_limit) = self.rate_limit {
+            let running_low = rate_limit.remaining < rate_limit.limit / 10;
+            let color = if rate_limit.remaining == 0 {
+                theme.error()
+            } else if running_low {
+                theme.warning()
+            } else {
+                theme.nav_fg()
+            };
+            let mut label = format!(" API {}/{} ", rate_limit.remaining, rate_limit.limit);
+            if running_low {
+                let reset = rate_limit.reset.with_timezone(&chrono::Local);
+                label.push_str(&format!("resets {} ", reset.format("%H:%M")));
+            }
+            block = block.title_bottom(
+                Line::from(Span::styled(label, Style::default().fg(color))).left_aligned(),
+            );
+        }
+
+        // Show the latest status message on the bottom border
+        if let Some(status) = status {
+            let color = if status.is_error {
+                theme.error()
+            } else {
+                theme.success()
+            };
+            block = block.title_bottom(
+                Line::from(Span::styled(
+                    format!(" {} ", status.text),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ))
+                .right_aligned(),
+            );
+        }
+
         let paragraph = Paragraph::new(nav_controls)
-            .block(
-                Block::default()
-                    .title(commit_info)
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(theme.border()))
-                    .style(Style::default().bg(theme.nav_bg()).fg(theme.nav_fg())),
-            )
+            .block(block)
             .alignment(Alignment::Center);
 
         f.render_widget(paragraph, area);
@@ -0,0 +1,125 @@
+use crate::{
+    github::models::PullRequest,
+    theme::Theme,
+    ui::{markdown::render_markdown, scroll::ScrollState},
+};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Screen showing the pull request's description, metadata and totals
+pub struct PrOverview {
+    pub scroll: ScrollState,
+}
+
+impl PrOverview {
+    pub fn new() -> Self {
+        Self {
+            scroll: ScrollState::new(),
+        }
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, theme: &Theme, pr: &PullRequest) {
+        let lines = overview_lines(pr, theme);
+        self.scroll.fit(&lines, area);
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(format!(" Pull request #{} ", pr.number))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border_focused()))
+                    .style(Style::default().bg(theme.bg()).fg(theme.fg())),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll.offset, 0));
+        f.render_widget(paragraph, area);
+        self.scroll.render_scrollbar(f, area, theme);
+    }
+}
+
+/// Title, metadata, totals and the rendered description
+fn overview_lines(pr: &PullRequest, theme: &Theme) -> Vec<Line<'static>> {
+    let label = Style::default().fg(theme.subtitle());
+    let value = Style::default().fg(theme.fg()).add_modifier(Modifier::BOLD);
+
+    let state_color = match pr.state.to_lowercase().as_str() {
+        "open" => theme.success(),
+        "closed" => theme.error(),
+        _ => theme.info(),
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            pr.title.clone(),
+            Style::default()
+                .fg(theme.header())
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!(" {} ", pr.state.to_uppercase()),
+                Style::default()
+                    .fg(theme.bg())
+                    .bg(state_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("  opened by ", label),
+            Span::styled(format!("@{}", pr.user.login), value),
+            Span::styled(
+                format!(" on {}", pr.created_at.format("%Y-%m-%d %H:%M")),
+                label,
+            ),
+            Span::styled(
+                format!(" · updated {}", pr.updated_at.format("%Y-%m-%d %H:%M")),
+                label,
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Merging ", label),
+            Span::styled(pr.head.label.clone(), value),
+            Span::styled(" into ", label),
+            Span::styled(pr.base.label.clone(), value),
+        ]),
+        Line::from(vec![
+            Span::styled(format!("{} commits", pr.commits), value),
+            Span::styled(" · ", label),
+            Span::styled(format!("{} files changed", pr.changed_files), value),
+            Span::styled(" · ", label),
+            Span::styled(
+                format!("+{}", pr.additions),
+                Style::default()
+                    .fg(theme.added())
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                format!("-{}", pr.deletions),
+                Style::default()
+                    .fg(theme.removed())
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(Span::styled(
+            "─".repeat(40),
+            Style::default().fg(theme.border()),
+        )),
+    ];
+
+    match pr.body.as_deref().map(str::trim) {
+        Some(body) if !body.is_empty() => lines.extend(render_markdown(body, theme)),
+        _ => lines.push(Line::from(Span::styled(
+            "No description provided.",
+            Style::default()
+                .fg(theme.context())
+                .add_modifier(Modifier::ITALIC),
+        ))),
+    }
+
+    lines
+}
@@ -0,0 +1,110 @@
+use crate::{
+    github::models::{NewReviewComment, ReviewEvent},
+    theme::Theme,
+    ui::{layout::centered_rect, CommentInput},
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Dialog for choosing a review outcome and writing its summary
+pub struct ReviewDialog {
+    pub event: ReviewEvent,
+    pub body: CommentInput,
+}
+
+impl ReviewDialog {
+    pub fn new() -> Self {
+        Self {
+            event: ReviewEvent::Comment,
+            body: CommentInput::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.event = ReviewEvent::Comment;
+        self.body.clear();
+    }
+
+    pub fn cycle_event(&mut self) {
+        self.event = self.event.next();
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme, drafts: &[NewReviewComment]) {
+        let popup_area = centered_rect(70, 60, area);
+        f.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title(" Submit review (Tab: change type, Ctrl+s: submit, Esc: cancel) ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border_focused()))
+            .style(Style::default().bg(theme.bg()).fg(theme.fg()));
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let draft_rows = drafts.len().min(5) as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),              // Event selector
+                Constraint::Min(3),                 // Summary body
+                Constraint::Length(draft_rows + 1), // Draft comments
+            ])
+            .split(inner);
+
+        // Event selector, with the chosen event highlighted
+        let mut selector = vec![Span::raw(" ")];
+        for event in ReviewEvent::ALL {
+            let (marker, style) = if event == self.event {
+                (
+                    "(•) ",
+                    Style::default()
+                        .fg(theme.nav_active())
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("( ) ", Style::default().fg(theme.context()))
+            };
+            selector.push(Span::styled(format!("{marker}{}   ", event.label()), style));
+        }
+        f.render_widget(Paragraph::new(Line::from(selector)), chunks[0]);
+
+        // Summary body
+        let body_block = Block::default()
+            .title(" Summary ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border()));
+        let body_area = body_block.inner(chunks[1]);
+        let lines: Vec<Line> = self.body.text.split('\n').map(Line::from).collect();
+        f.render_widget(
+            Paragraph::new(lines)
+                .block(body_block)
+                .wrap(Wrap { trim: false }),
+            chunks[1],
+        );
+
+        // Draft comments that will be included in the review
+        let mut draft_lines = vec![Line::from(Span::styled(
+            format!(" {} draft comment(s) will be included", drafts.len()),
+            Style::default().fg(theme.info()),
+        ))];
+        for draft in drafts.iter().take(draft_rows as usize) {
+            let first_line = draft.body.lines().next().unwrap_or("");
+            draft_lines.push(Line::from(Span::styled(
+                format!("   {}:{}  {}", draft.path, draft.line, first_line),
+                Style::default().fg(theme.context()),
+            )));
+        }
+        f.render_widget(Paragraph::new(draft_lines), chunks[2]);
+
+        let (row, col) = self.body.cursor_position();
+        f.set_cursor_position((
+            body_area.x + col.min(body_area.width.saturating_sub(1)),
+            body_area.y + row.min(body_area.height.saturating_sub(1)),
+        ));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::Line,
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+
+/// Scroll position of a wrapped, read-only screen such as the overview
+pub struct ScrollState {
+    pub offset: u16,
+    max: u16,
+    viewport_height: u16,
+}
+
+impl ScrollState {
+    pub fn new() -> Self {
+        Self {
+            offset: 0,
+            max: 0,
+            viewport_height: 20,
+        }
+    }
+
+    pub fn scroll_up(&mut self, amount: u16) {
+        self.offset = self.offset.saturating_sub(amount);
+    }
+
+    pub fn scroll_down(&mut self, amount: u16) {
+        self.offset = self.offset.saturating_add(amount).min(self.max);
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.viewport_height.saturating_sub(2));
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_down(self.viewport_height.saturating_sub(2));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.offset = self.max;
+    }
+
+    /// Update the scroll bounds for `lines` shown wrapped inside a bordered `area`
+    pub fn fit(&mut self, lines: &[Line], area: Rect) {
+        // Estimate the wrapped height so scrolling stops at the end of the content
+        let width = area.width.saturating_sub(3).max(1) as usize;
+        let total_rows: usize = lines
+            .iter()
+            .map(|line| line.width().div_ceil(width).max(1))
+            .sum();
+        self.viewport_height = area.height.saturating_sub(2);
+        self.max = total_rows.saturating_sub(self.viewport_height as usize) as u16;
+        self.offset = self.offset.min(self.max);
+    }
+
+    pub fn render_scrollbar(&self, f: &mut Frame, area: Rect, theme: &Theme) {
+        if self.max == 0 {
+            return;
+        }
+
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some("↑"))
+            .end_symbol(Some("↓"))
+            .thumb_style(Style::default().fg(theme.scrollbar_thumb()))
+            .track_style(Style::default().fg(theme.scrollbar()));
+        let mut scrollbar_state =
+            ScrollbarState::new(self.max as usize).position(self.offset as usize);
+        f.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+    }
+}